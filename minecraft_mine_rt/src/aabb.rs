@@ -1,3 +1,4 @@
+use crate::color::Color;
 use crate::math::{Vec3, Ray};
 
 /// Índice de cara 
@@ -9,6 +10,17 @@ pub fn face_from_normal(n: Vec3) -> u8 {
     else if n.z < -0.5 { 4 } else { 5 }
 }
 
+/// Máscaras de cara para `Tint::faces` (bit = índice de cara)
+pub const FACE_TOP: u8 = 1 << 3;
+pub const ALL_FACES: u8 = 0b11_1111;
+
+/// Color que multiplica la textura en las caras marcadas (césped, hojas).
+#[derive(Clone, Copy, Debug)]
+pub struct Tint {
+    pub color: Color,
+    pub faces: u8,
+}
+
 #[derive(Clone)]
 pub struct Aabb {
     pub min: Vec3,
@@ -16,6 +28,19 @@ pub struct Aabb {
     pub mat_id: usize,
     /// Orden: 0:-X, 1:+X, 2:-Y, 3:+Y, 4:-Z, 5:+Z
     pub face_tex: Option<[usize; 6]>,
+    pub tint: Option<Tint>,
+    /// UV que se repiten cada unidad de mundo: las cajas fusionadas conservan
+    /// el mosaico de un bloque por textura.
+    pub tiled: bool,
+    /// Velocidad en bloques por segundo: `min`/`max` son la posición en el segundo 0
+    /// y el rayo la ve donde está en su `time`
+    pub motion: Option<Vec3>,
+}
+
+impl Aabb {
+    pub fn new(min: Vec3, max: Vec3, mat_id: usize, face_tex: Option<[usize; 6]>) -> Self {
+        Self { min, max, mat_id, face_tex, tint: None, tiled: false, motion: None }
+    }
 }
 
 pub struct Hit {
@@ -27,67 +52,76 @@ pub struct Hit {
     pub mat_id: usize,
     pub face_idx: u8,
     pub face_tex: Option<[usize; 6]>,
+    pub tint: Option<Tint>,
 }
 
 pub fn intersect_aabb(ray: &Ray, b: &Aabb) -> Option<Hit> {
+    // una caja en movimiento se prueba quieta, con el rayo desplazado al revés
+    let shift = b.motion.map_or(Vec3::default(), |v| v.mul(ray.time));
+    let o = ray.o.sub(shift);
     let inv = Vec3::new(1.0 / ray.d.x, 1.0 / ray.d.y, 1.0 / ray.d.z);
 
-    let mut t1 = (b.min.x - ray.o.x) * inv.x;
-    let mut t2 = (b.max.x - ray.o.x) * inv.x;
+    let mut t1 = (b.min.x - o.x) * inv.x;
+    let mut t2 = (b.max.x - o.x) * inv.x;
     let mut tmin = t1.min(t2);
     let mut tmax = t1.max(t2);
 
-    t1 = (b.min.y - ray.o.y) * inv.y;
-    t2 = (b.max.y - ray.o.y) * inv.y;
+    t1 = (b.min.y - o.y) * inv.y;
+    t2 = (b.max.y - o.y) * inv.y;
     tmin = tmin.max(t1.min(t2));
     tmax = tmax.min(t1.max(t2));
 
-    t1 = (b.min.z - ray.o.z) * inv.z;
-    t2 = (b.max.z - ray.o.z) * inv.z;
+    t1 = (b.min.z - o.z) * inv.z;
+    t2 = (b.max.z - o.z) * inv.z;
     tmin = tmin.max(t1.min(t2));
     tmax = tmax.min(t1.max(t2));
 
     if tmax >= tmin.max(0.0) {
         let t = tmin.max(0.0);
-        let p = ray.o.add(ray.d.mul(t));
+        let p = o.add(ray.d.mul(t));
         let eps = 1e-3;
+        // posición en la cara (lo..hi, invertida con signo) -> coordenada de textura;
+        // en mosaico se toma la del bloque del mundo, así las piezas menores que un
+        // bloque (losas, escalones) muestran su parte de la textura
+        let uv = |x: f32, lo: f32, hi: f32| if b.tiled { x.rem_euclid(1.0) } else { (x - lo) / (hi - lo) };
 
         let (u, v, n) = if (p.x - b.min.x).abs() < eps {
-            let u = (p.z - b.min.z) / (b.max.z - b.min.z);
-            let v = (p.y - b.min.y) / (b.max.y - b.min.y);
+            let u = uv(p.z, b.min.z, b.max.z);
+            let v = uv(p.y, b.min.y, b.max.y);
             (u, v, Vec3::new(-1.0, 0.0, 0.0))
         } else if (p.x - b.max.x).abs() < eps {
-            let u = (b.max.z - p.z) / (b.max.z - b.min.z);
-            let v = (p.y - b.min.y) / (b.max.y - b.min.y);
+            let u = uv(-p.z, -b.max.z, -b.min.z);
+            let v = uv(p.y, b.min.y, b.max.y);
             (u, v, Vec3::new(1.0, 0.0, 0.0))
         } else if (p.y - b.min.y).abs() < eps {
-            let u = (p.x - b.min.x) / (b.max.x - b.min.x);
-            let v = (p.z - b.min.z) / (b.max.z - b.min.z);
+            let u = uv(p.x, b.min.x, b.max.x);
+            let v = uv(p.z, b.min.z, b.max.z);
             (u, v, Vec3::new(0.0, -1.0, 0.0))
         } else if (p.y - b.max.y).abs() < eps {
-            let u = (p.x - b.min.x) / (b.max.x - b.min.x);
-            let v = (b.max.z - p.z) / (b.max.z - b.min.z);
+            let u = uv(p.x, b.min.x, b.max.x);
+            let v = uv(-p.z, -b.max.z, -b.min.z);
             (u, v, Vec3::new(0.0, 1.0, 0.0))
         } else if (p.z - b.min.z).abs() < eps {
-            let u = (p.x - b.min.x) / (b.max.x - b.min.x);
-            let v = (p.y - b.min.y) / (b.max.y - b.min.y);
+            let u = uv(p.x, b.min.x, b.max.x);
+            let v = uv(p.y, b.min.y, b.max.y);
             (u, v, Vec3::new(0.0, 0.0, -1.0))
         } else {
-            let u = (b.max.x - p.x) / (b.max.x - b.min.x);
-            let v = (p.y - b.min.y) / (b.max.y - b.min.y);
+            let u = uv(-p.x, -b.max.x, -b.min.x);
+            let v = uv(p.y, b.min.y, b.max.y);
             (u, v, Vec3::new(0.0, 0.0, 1.0))
         };
 
         let face_idx = face_from_normal(n);
         Some(Hit {
             t,
-            p,
+            p: p.add(shift),
             n,
             u,
             v,
             mat_id: b.mat_id,
             face_idx,
-            face_tex: b.face_tex.clone(),
+            face_tex: b.face_tex,
+            tint: b.tint,
         })
     } else {
         None
@@ -0,0 +1,168 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use crate::nbt::{self, Tag};
+use crate::schematic::{state_name, Schematic, AIR};
+
+// Mundos guardados en formato Anvil: `region/r.<rx>.<rz>.mca`, 32×32 chunks por archivo.
+// Se leen secciones con paleta (1.13 en adelante); los chunks ausentes quedan vacíos.
+
+const SECTOR: usize = 4096;
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+struct Region {
+    dir: String,
+    data: Vec<u8>,
+}
+
+impl Region {
+    /// `None` si el archivo no existe (zona nunca generada).
+    fn open(dir: &str, rx: i32, rz: i32) -> io::Result<Option<Self>> {
+        match fs::read(format!("{}/r.{}.{}.mca", dir, rx, rz)) {
+            Ok(data) if data.len() >= 2 * SECTOR => Ok(Some(Self { dir: dir.to_string(), data })),
+            Ok(data) if data.is_empty() => Ok(None),
+            Ok(_) => Err(bad("región sin tabla de sectores")),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// NBT del chunk (cx, cz) en coordenadas absolutas de chunk, si está guardado.
+    fn chunk(&self, cx: i32, cz: i32) -> io::Result<Option<Tag>> {
+        let i = 4 * ((cx & 31) + (cz & 31) * 32) as usize;
+        let e = &self.data[i..i + 4];
+        let offset = (e[0] as usize) << 16 | (e[1] as usize) << 8 | e[2] as usize;
+        if offset == 0 || e[3] == 0 { return Ok(None); }
+        let start = offset * SECTOR;
+        let head = self.data.get(start..start + 5).ok_or_else(|| bad("chunk fuera del archivo"))?;
+        let len = u32::from_be_bytes(head[0..4].try_into().unwrap()) as usize;
+        if len == 0 { return Ok(None); }
+        let kind = head[4];
+        let payload = if kind & 0x80 != 0 {
+            // chunk enorme guardado aparte en c.<cx>.<cz>.mcc
+            fs::read(format!("{}/c.{}.{}.mcc", self.dir, cx, cz))?
+        } else {
+            self.data.get(start + 5..start + 4 + len).ok_or_else(|| bad("chunk truncado"))?.to_vec()
+        };
+        match kind & 0x7f {
+            1..=3 => Ok(Some(nbt::read(&payload)?.1)), // gzip, zlib o sin comprimir
+            4 => Err(bad("chunks comprimidos con LZ4 no soportados")),
+            _ => Err(bad("compresión de chunk desconocida")),
+        }
+    }
+}
+
+/// Sección de 16³ con su paleta y los índices empaquetados en longs.
+struct Section<'a> {
+    y: i32,
+    palette: &'a [Tag],
+    data: Option<&'a [i64]>,
+}
+
+fn sections(chunk: &Tag) -> io::Result<(Vec<Section<'_>>, bool)> {
+    // hasta 20w17a (1.16) los índices podían cruzar de un long al siguiente
+    let spanning = chunk.get("DataVersion").and_then(Tag::as_int).unwrap_or(0) < 2529;
+    let (list, new) = match chunk.get("sections") {
+        Some(l) => (l, true),                                              // 1.18+
+        None => match chunk.get("Level").and_then(|l| l.get("Sections")) { // 1.13–1.17
+            Some(l) => (l, false),
+            None => return Ok((Vec::new(), spanning)),
+        },
+    };
+    let mut out = Vec::new();
+    for s in list.as_list().ok_or_else(|| bad("sections no es una lista"))? {
+        let y = s.get("Y").and_then(Tag::as_int).ok_or_else(|| bad("sección sin Y"))? as i32;
+        let (pal, data) = if new {
+            let bs = s.get("block_states");
+            (bs.and_then(|b| b.get("palette")), bs.and_then(|b| b.get("data")))
+        } else {
+            (s.get("Palette"), s.get("BlockStates"))
+        };
+        let Some(pal) = pal else {
+            if s.get("Blocks").is_some() { return Err(bad("mundos anteriores a 1.13 no soportados")); }
+            continue; // sección vacía (solo luz)
+        };
+        let palette = pal.as_list().ok_or_else(|| bad("paleta inválida"))?;
+        let data = match data {
+            Some(Tag::LongArray(d)) => Some(d.as_slice()),
+            Some(_) => return Err(bad("datos de sección inválidos")),
+            None => None,
+        };
+        out.push(Section { y, palette, data });
+    }
+    Ok((out, spanning))
+}
+
+impl Section<'_> {
+    /// Índice en la paleta del bloque `i = (y * 16 + z) * 16 + x`.
+    fn state(&self, i: usize, spanning: bool) -> io::Result<usize> {
+        let Some(data) = self.data else { return Ok(0) }; // paleta de un solo estado
+        let bits = (usize::BITS - (self.palette.len().max(2) - 1).leading_zeros()).max(4) as usize;
+        let mask = (1u64 << bits) - 1;
+        let word = |w: usize| data.get(w).map(|&v| v as u64).ok_or_else(|| bad("datos de sección truncados"));
+        let v = if spanning {
+            let (w, off) = (i * bits / 64, i * bits % 64);
+            let mut v = word(w)? >> off;
+            if off + bits > 64 { v |= word(w + 1)? << (64 - off); }
+            v & mask
+        } else {
+            let per = 64 / bits;
+            (word(i / per)? >> (i % per * bits)) & mask
+        };
+        Ok(v as usize)
+    }
+}
+
+/// Extrae los bloques de la caja `[a, b]` (coordenadas de bloque, inclusivas)
+/// del mundo cuya carpeta de regiones es `dir`. Las posiciones quedan relativas
+/// a la esquina mínima, como en una estructura.
+pub fn extract(dir: &str, a: [i32; 3], b: [i32; 3]) -> io::Result<Schematic> {
+    let lo = [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])];
+    let hi = [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])];
+    let mut palette = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut regions: HashMap<(i32, i32), Option<Region>> = HashMap::new();
+    let mut blocks = Vec::new();
+
+    for cz in lo[2] >> 4..=hi[2] >> 4 {
+        for cx in lo[0] >> 4..=hi[0] >> 4 {
+            let region = match regions.entry((cx >> 5, cz >> 5)) {
+                Entry::Occupied(e) => e.into_mut(),
+                Entry::Vacant(e) => e.insert(Region::open(dir, cx >> 5, cz >> 5)?),
+            };
+            let Some(region) = region else { continue };
+            let chunk = region.chunk(cx, cz).map_err(|e| bad(&format!("chunk ({}, {}): {}", cx, cz, e)))?;
+            let Some(chunk) = chunk else { continue };
+            let (secs, spanning) = sections(&chunk)?;
+            for s in secs {
+                let (y0, y1) = ((s.y * 16).max(lo[1]), (s.y * 16 + 15).min(hi[1]));
+                if y0 > y1 { continue; }
+                // paleta local -> paleta global; None para el aire
+                let map: Vec<Option<usize>> = s.palette.iter().map(|p| {
+                    let name = state_name(p).unwrap_or_else(|| "minecraft:air".to_string());
+                    if AIR.contains(&name.as_str()) { return None; }
+                    Some(*index.entry(name.clone()).or_insert_with(|| {
+                        palette.push(name);
+                        palette.len() - 1
+                    }))
+                }).collect();
+                if map.iter().all(Option::is_none) { continue; }
+                for y in y0..=y1 {
+                    for z in (cz * 16).max(lo[2])..=(cz * 16 + 15).min(hi[2]) {
+                        for x in (cx * 16).max(lo[0])..=(cx * 16 + 15).min(hi[0]) {
+                            let i = (((y & 15) * 16 + (z & 15)) * 16 + (x & 15)) as usize;
+                            let st = s.state(i, spanning)?;
+                            let Some(&Some(g)) = map.get(st) else { continue };
+                            blocks.push(([x - lo[0], y - lo[1], z - lo[2]], g));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let size = [hi[0] - lo[0] + 1, hi[1] - lo[1] + 1, hi[2] - lo[2] + 1];
+    Ok(Schematic { size, palette, blocks })
+}
@@ -0,0 +1,84 @@
+use crate::color::Color;
+use crate::ppm::load_ppm;
+
+/// Clima de un bioma; decide el color del césped y del follaje.
+#[derive(Clone, Copy, Debug)]
+pub struct Biome {
+    pub temperature: f32,
+    pub humidity: f32,
+}
+
+impl Biome {
+    pub fn new(temperature: f32, humidity: f32) -> Self { Self { temperature, humidity } }
+
+    /// Valores de los biomas del juego.
+    pub fn by_name(name: &str) -> Option<Self> {
+        let (t, h) = match name {
+            "plains" => (0.8, 0.4),
+            "forest" => (0.7, 0.8),
+            "jungle" => (0.95, 0.9),
+            "savanna" => (1.2, 0.0),
+            "taiga" => (0.25, 0.8),
+            "swamp" => (0.8, 0.9),
+            _ => return None,
+        };
+        Some(Self::new(t, h))
+    }
+}
+
+/// Qué colormap tiñe un bloque. Abeto y abedul usan un color fijo, como en el juego.
+#[derive(Clone, Copy, Debug)]
+pub enum TintKind { Grass, Foliage, Spruce, Birch }
+
+/// Colormap triangular estilo `grass.png`/`foliage.png` (256x256).
+/// x = (1 - temp) * 255, y = (1 - hum * temp) * 255.
+pub enum Colormap {
+    Image { w: usize, h: usize, data: Vec<u8> },
+    /// Respaldo: interpola los tres vértices del triángulo.
+    Corners { hot_wet: Color, hot_dry: Color, cold: Color },
+}
+
+impl Colormap {
+    /// Carga el PPM de `path`; si no existe interpola los vértices dados.
+    pub fn load(path: &str, hot_wet: Color, hot_dry: Color, cold: Color) -> Self {
+        match load_ppm(path) {
+            Ok(img) => Colormap::Image { w: img.w, h: img.h, data: img.data },
+            Err(_) => Colormap::Corners { hot_wet, hot_dry, cold },
+        }
+    }
+
+    pub fn grass() -> Self {
+        Self::load(
+            "assets/textures/colormap/grass.ppm",
+            Color::from_u8(71, 205, 51),
+            Color::from_u8(191, 183, 85),
+            Color::from_u8(128, 180, 151),
+        )
+    }
+
+    pub fn foliage() -> Self {
+        Self::load(
+            "assets/textures/colormap/foliage.ppm",
+            Color::from_u8(26, 191, 0),
+            Color::from_u8(174, 164, 42),
+            Color::from_u8(96, 161, 123),
+        )
+    }
+
+    pub fn lookup(&self, b: Biome) -> Color {
+        let t = b.temperature.clamp(0.0, 1.0);
+        let h = b.humidity.clamp(0.0, 1.0) * t;
+        match self {
+            Colormap::Image { w, h: ih, data } => {
+                let x = (((1.0 - t) * (*w - 1) as f32) as usize).min(*w - 1);
+                let y = (((1.0 - h) * (*ih - 1) as f32) as usize).min(*ih - 1);
+                let idx = (y * w + x) * 3;
+                Color::from_u8(data[idx], data[idx + 1], data[idx + 2])
+            }
+            // pesos baricéntricos: frío = 1-t, húmedo = h, seco = t-h
+            Colormap::Corners { hot_wet, hot_dry, cold } => {
+                cold.mul(1.0 - t).add(hot_wet.mul(h)).add(hot_dry.mul(t - h))
+            }
+        }
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::HashMap;
+
+use crate::aabb::{Aabb, Tint, ALL_FACES, FACE_TOP};
+use crate::biome::{Biome, Colormap, TintKind};
+use crate::color::Color;
+use crate::material::Material;
+use crate::math::Vec3;
+use crate::shape::Surface;
+use crate::texture::{Plant, TexKind, Texture};
+
+/// Índice de un bloque dentro de `BlockRegistry`.
+pub type BlockId = usize;
+
+/// Regla de texturas por cara de un bloque.
+/// Orden de `Aabb::face_tex`: 0:-X, 1:+X, 2:-Y, 3:+Y, 4:-Z, 5:+Z
+pub enum Faces<'a> {
+    /// Misma textura en las seis caras (tierra, piedra, vidrio…)
+    All(&'a str),
+    /// Tapa, lados y fondo (césped, troncos)
+    Column { top: &'a str, side: &'a str, bottom: &'a str },
+    /// Una textura por cara, en el orden de arriba
+    Each([&'a str; 6]),
+}
+
+/// Orientación horizontal de escaleras y antorchas de pared.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Facing { North, East, South, West }
+
+impl Facing {
+    pub const ALL: [Facing; 4] = [Facing::North, Facing::East, Facing::South, Facing::West];
+
+    pub fn name(self) -> &'static str {
+        match self { Facing::North => "north", Facing::East => "east", Facing::South => "south", Facing::West => "west" }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> { Facing::ALL.into_iter().find(|f| f.name() == name) }
+
+    /// Tras reflejar en x (si `mirror`) y girar `turns` cuartos de vuelta en sentido horario visto desde arriba.
+    pub fn oriented(self, turns: i32, mirror: bool) -> Self {
+        let f = match (mirror, self) { (true, Facing::East) => Facing::West, (true, Facing::West) => Facing::East, _ => self };
+        Facing::ALL[(f as i32 + turns).rem_euclid(4) as usize]
+    }
+
+    /// Gira un punto del bloque (x, z en 0..1) desde la orientación norte a esta.
+    fn turn(self, x: f32, z: f32) -> (f32, f32) {
+        match self {
+            Facing::North => (x, z),
+            Facing::East => (1.0 - z, x),
+            Facing::South => (1.0 - x, 1.0 - z),
+            Facing::West => (z, 1.0 - x),
+        }
+    }
+}
+
+/// Geometría con la que se coloca un bloque.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Model {
+    Cube,
+    /// Dos planos cruzados en el centro del bloque (pasto, flores, brotes)
+    Cross,
+    /// Medio bloque, abajo o arriba
+    Slab { top: bool },
+    /// Media losa más un escalón con el lado alto hacia `facing`; `top` la pone del revés
+    Stairs { facing: Facing, top: bool },
+    /// Poste con travesaños hacia las vallas y bloques enteros vecinos
+    Fence,
+    /// Lámina fina que se une a los paneles y bloques enteros vecinos
+    Pane,
+    /// Antorcha de pie o, con `wall`, apoyada en la pared contraria a esa dirección
+    Torch { wall: Option<Facing> },
+}
+
+impl Model {
+    /// Vallas y paneles dependen de sus vecinos.
+    fn connects(self) -> bool { matches!(self, Model::Fence | Model::Pane) }
+
+    /// Cajas del modelo dentro del bloque (0..1); `links` marca los vecinos unidos
+    /// con un bit por dirección en el orden de `Facing`.
+    fn parts(self, links: u8) -> Vec<(Vec3, Vec3)> {
+        let b = |x0: f32, y0: f32, z0: f32, x1: f32, y1: f32, z1: f32| (Vec3::new(x0, y0, z0), Vec3::new(x1, y1, z1));
+        // caja descrita mirando al norte, girada a `f`
+        let turned = |f: Facing, (lo, hi): (Vec3, Vec3)| {
+            let (ax, az) = f.turn(lo.x, lo.z);
+            let (bx, bz) = f.turn(hi.x, hi.z);
+            b(ax.min(bx), lo.y, az.min(bz), ax.max(bx), hi.y, az.max(bz))
+        };
+        let px = 1.0 / 16.0;
+        match self {
+            Model::Cube | Model::Cross => vec![b(0.0, 0.0, 0.0, 1.0, 1.0, 1.0)],
+            Model::Slab { top } => {
+                let y = if top { 0.5 } else { 0.0 };
+                vec![b(0.0, y, 0.0, 1.0, y + 0.5, 1.0)]
+            }
+            Model::Stairs { facing, top } => {
+                let (slab, step) = if top { (0.5, 0.0) } else { (0.0, 0.5) };
+                vec![b(0.0, slab, 0.0, 1.0, slab + 0.5, 1.0), turned(facing, b(0.0, step, 0.0, 1.0, step + 0.5, 0.5))]
+            }
+            Model::Fence => {
+                let mut v = vec![b(6.0 * px, 0.0, 6.0 * px, 10.0 * px, 1.0, 10.0 * px)];
+                for f in Facing::ALL.into_iter().filter(|&f| links & (1 << f as u8) != 0) {
+                    for (y0, y1) in [(6.0 * px, 9.0 * px), (12.0 * px, 15.0 * px)] {
+                        v.push(turned(f, b(7.0 * px, y0, 0.0, 9.0 * px, y1, 6.0 * px)));
+                    }
+                }
+                v
+            }
+            Model::Pane => {
+                // sola, la lámina va de oeste a este
+                let links = if links == 0 { 0b1010 } else { links };
+                let mut v = vec![b(7.0 * px, 0.0, 7.0 * px, 9.0 * px, 1.0, 9.0 * px)];
+                for f in Facing::ALL.into_iter().filter(|&f| links & (1 << f as u8) != 0) {
+                    v.push(turned(f, b(7.0 * px, 0.0, 0.0, 9.0 * px, 1.0, 7.0 * px)));
+                }
+                v
+            }
+            Model::Torch { wall: None } => vec![b(7.0 * px, 0.0, 7.0 * px, 9.0 * px, 10.0 * px, 9.0 * px)],
+            Model::Torch { wall: Some(f) } => vec![turned(f, b(7.0 * px, 3.0 * px, 14.0 * px, 9.0 * px, 13.0 * px, 1.0))],
+        }
+    }
+}
+
+/// Nombre de estado canónico: solo las propiedades que cambian la forma, en orden
+/// alfabético (`oak_stairs` + `half=top,facing=east` -> `oak_stairs[facing=east,half=top]`).
+pub fn state_name<'a>(base: &str, props: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut keep: Vec<String> = props
+        .filter(|(k, _)| matches!(*k, "facing" | "half" | "type"))
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+    keep.sort();
+    if keep.is_empty() { base.to_string() } else { format!("{}[{}]", base, keep.join(",")) }
+}
+
+/// Geometría de cada bloque con la esquina en el origen, sacada del registro para
+/// poder compilar rejillas sin él. Vallas y paneles tienen una variante por
+/// cada combinación de vecinos unidos.
+pub struct Shapes {
+    parts: Vec<Vec<Vec<Aabb>>>,
+    models: Vec<Model>,
+}
+
+impl Shapes {
+    pub fn get(&self, id: BlockId, links: u8) -> &[Aabb] {
+        let v = &self.parts[id];
+        &v[links as usize % v.len()]
+    }
+
+    pub fn connects(&self, id: BlockId) -> bool { self.models[id].connects() }
+
+    /// Las vallas se unen a vallas, los paneles a paneles, y ambos a bloques enteros.
+    pub fn joins(&self, id: BlockId, other: BlockId) -> bool {
+        let (a, b) = (self.models[id], self.models[other]);
+        b == Model::Cube || (a.connects() && a == b)
+    }
+}
+
+#[derive(Clone)]
+pub struct BlockDef {
+    /// Nombre en el registro (con estado para las variantes: `oak_stairs[facing=east,half=top]`)
+    pub name: String,
+    pub mat_id: usize,
+    pub face_tex: [usize; 6],
+    /// Colormap y caras que se tiñen según el bioma
+    pub tint: Option<(TintKind, u8)>,
+    pub model: Model,
+}
+
+/// Registro de texturas, materiales y tipos de bloque por nombre.
+/// Los nombres mal escritos fallan en voz alta en vez de caer en silencio
+/// a una textura procedural.
+pub struct BlockRegistry {
+    pub textures: Vec<Texture>,
+    pub materials: Vec<Material>,
+    /// Bioma con el que se tiñen los bloques al colocarlos
+    pub biome: Biome,
+    grass_map: Colormap,
+    foliage_map: Colormap,
+    blocks: Vec<BlockDef>,
+    tex_ids: HashMap<String, usize>,
+    mat_ids: HashMap<String, usize>,
+    block_ids: HashMap<String, BlockId>,
+}
+
+impl BlockRegistry {
+    pub fn new() -> Self {
+        Self {
+            textures: Vec::new(),
+            materials: Vec::new(),
+            biome: Biome::new(0.8, 0.4), // llanura
+            grass_map: Colormap::grass(),
+            foliage_map: Colormap::foliage(),
+            blocks: Vec::new(),
+            tex_ids: HashMap::new(),
+            mat_ids: HashMap::new(),
+            block_ids: HashMap::new(),
+        }
+    }
+
+    /// Carga el PPM de `path`; si no existe avisa y usa la procedural `fallback`.
+    pub fn texture(&mut self, name: &str, path: &str, fallback: TexKind) -> usize {
+        let tex = Texture::from_ppm(path).unwrap_or_else(|| {
+            eprintln!("aviso: textura '{}' no encontrada en {}, usando procedural", name, path);
+            Texture::new(fallback)
+        });
+        self.add_texture(name, tex)
+    }
+
+    /// Tira animada en `path`; si no existe usa `fallback` sin avisar
+    /// (normalmente una versión con flujo de la textura estática).
+    pub fn animated(&mut self, name: &str, path: &str, frame_time: f32, fallback: Texture) -> usize {
+        let tex = Texture::from_ppm_strip(path, frame_time).unwrap_or(fallback);
+        self.add_texture(name, tex)
+    }
+
+    pub fn add_texture(&mut self, name: &str, tex: Texture) -> usize {
+        let id = self.textures.len();
+        self.textures.push(tex);
+        self.tex_ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn material(&mut self, name: &str, mat: Material) -> usize {
+        let id = self.materials.len();
+        self.materials.push(mat);
+        self.mat_ids.insert(name.to_string(), id);
+        id
+    }
+
+    pub fn block(&mut self, name: &str, material: &str, faces: Faces) -> BlockId {
+        let mat_id = self.mat(material);
+        let face_tex = match faces {
+            Faces::All(t) => [self.tex(t); 6],
+            Faces::Column { top, side, bottom } => {
+                let (t, s, b) = (self.tex(top), self.tex(side), self.tex(bottom));
+                [s, s, b, t, s, s]
+            }
+            Faces::Each(names) => names.map(|t| self.tex(t)),
+        };
+        let id = self.blocks.len();
+        self.blocks.push(BlockDef { name: name.to_string(), mat_id, face_tex, tint: None, model: Model::Cube });
+        self.block_ids.insert(name.to_string(), id);
+        id
+    }
+
+    /// Marca las caras `faces` del bloque para teñirse con el colormap `kind`.
+    pub fn tinted(&mut self, id: BlockId, kind: TintKind, faces: u8) {
+        self.blocks[id].tint = Some((kind, faces));
+    }
+
+    /// Cambia el modelo y registra un bloque por cada estado con otra forma
+    /// (`oak_slab[type=top]`, `oak_stairs[facing=east,half=top]`…); va después de `tinted`.
+    pub fn model(&mut self, id: BlockId, model: Model) {
+        self.blocks[id].model = model;
+        let mut states = Vec::new();
+        match model {
+            Model::Slab { .. } => {
+                states.push(("type=bottom".to_string(), Model::Slab { top: false }));
+                states.push(("type=top".to_string(), Model::Slab { top: true }));
+                states.push(("type=double".to_string(), Model::Cube));
+            }
+            Model::Stairs { .. } => {
+                for facing in Facing::ALL {
+                    for (half, top) in [("bottom", false), ("top", true)] {
+                        states.push((format!("facing={},half={}", facing.name(), half), Model::Stairs { facing, top }));
+                    }
+                }
+            }
+            Model::Torch { wall: Some(_) } => {
+                for f in Facing::ALL { states.push((format!("facing={}", f.name()), Model::Torch { wall: Some(f) })); }
+            }
+            _ => {}
+        }
+        for (state, model) in states {
+            let name = format!("{}[{}]", self.blocks[id].name, state);
+            let def = BlockDef { name: name.clone(), model, ..self.blocks[id].clone() };
+            self.block_ids.insert(name, self.blocks.len());
+            self.blocks.push(def);
+        }
+    }
+
+    pub fn tint_color(&self, kind: TintKind) -> Color {
+        match kind {
+            TintKind::Grass => self.grass_map.lookup(self.biome),
+            TintKind::Foliage => self.foliage_map.lookup(self.biome),
+            TintKind::Spruce => Color::from_u8(0x61, 0x99, 0x61),
+            TintKind::Birch => Color::from_u8(0x80, 0xa7, 0x55),
+        }
+    }
+
+    pub fn tex(&self, name: &str) -> usize {
+        self.find_tex(name).unwrap_or_else(|| panic!("textura desconocida: '{}'", name))
+    }
+
+    pub fn mat(&self, name: &str) -> usize {
+        self.find_mat(name).unwrap_or_else(|| panic!("material desconocido: '{}'", name))
+    }
+
+    pub fn id(&self, name: &str) -> BlockId {
+        self.find(name).unwrap_or_else(|| panic!("bloque desconocido: '{}'", name))
+    }
+
+    pub fn find_tex(&self, name: &str) -> Option<usize> { self.tex_ids.get(name).copied() }
+    pub fn find_mat(&self, name: &str) -> Option<usize> { self.mat_ids.get(name).copied() }
+    pub fn find(&self, name: &str) -> Option<BlockId> { self.block_ids.get(name).copied() }
+
+    /// Busca un bloque por su nombre del juego (`minecraft:oak_planks`):
+    /// primero el nombre exacto, luego sin espacio de nombres y por último
+    /// un bloque parecido del registro (`*_planks` -> planks, `cobblestone` -> cobble…).
+    /// Con propiedades (`oak_stairs[facing=east]`) busca la variante de esa forma,
+    /// y si no la hay se queda con el bloque base.
+    pub fn resolve(&self, name: &str) -> Option<BlockId> {
+        let Some((base, props)) = name.split_once('[') else { return self.resolve_base(name) };
+        let id = self.resolve_base(base)?;
+        let props = props.trim_end_matches(']').split(',').filter_map(|p| p.split_once('='));
+        Some(self.find(&state_name(&self.blocks[id].name, props)).unwrap_or(id))
+    }
+
+    fn resolve_base(&self, name: &str) -> Option<BlockId> {
+        if let Some(id) = self.find(name) { return Some(id); }
+        let short = name.strip_prefix("minecraft:").unwrap_or(name);
+        if let Some(id) = self.find(short) { return Some(id); }
+        if let Some(ore) = short.strip_prefix("deepslate_")
+            && let Some(id) = self.find(ore)
+        {
+            return Some(id);
+        }
+        let alias = match short {
+            "grass_block" | "podzol" | "mycelium" | "moss_block" => "grass",
+            "coarse_dirt" | "rooted_dirt" | "farmland" | "dirt_path" | "mud" => "dirt",
+            "cobblestone" | "mossy_cobblestone" | "cobbled_deepslate" => "cobble",
+            "iron_block" | "gold_block" | "copper_block" => "metal",
+            "red_sand" | "sandstone" | "smooth_sandstone" | "cut_sandstone" | "gravel" => "sand",
+            "andesite" | "diorite" | "granite" | "deepslate" | "tuff" | "smooth_stone" | "bedrock" => "stone",
+            "soul_torch" | "redstone_torch" => "torch",
+            "soul_wall_torch" | "redstone_wall_torch" => "wall_torch",
+            s if s.ends_with("_stairs") && (s.contains("stone") || s.contains("brick")) => "cobble_stairs",
+            s if s.ends_with("_stairs") => "oak_stairs",
+            s if s.ends_with("_slab") && (s.contains("stone") || s.contains("brick")) => "stone_slab",
+            s if s.ends_with("_slab") => "oak_slab",
+            s if s.ends_with("_fence") => "oak_fence",
+            s if s.ends_with("glass_pane") || s == "iron_bars" => "glass_pane",
+            s if s.ends_with("stone_bricks") || s.starts_with("polished_") => "stone",
+            s if s.ends_with("_planks") => "planks",
+            s if s.ends_with("_log") || s.ends_with("_wood") || s.ends_with("_stem") || s.ends_with("_hyphae") => "log",
+            "tall_grass" | "fern" | "large_fern" => "short_grass",
+            "azure_bluet" | "oxeye_daisy" | "white_tulip" | "lily_of_the_valley" => "dandelion",
+            "red_tulip" | "rose_bush" | "orange_tulip" => "poppy",
+            "blue_orchid" | "allium" | "pink_tulip" => "cornflower",
+            s if s.ends_with("_leaves") => "leaves",
+            s if s.contains("glass") => "glass",
+            _ => return None,
+        };
+        self.find(alias)
+    }
+
+    pub fn def(&self, id: BlockId) -> &BlockDef { &self.blocks[id] }
+
+    /// Material, texturas y tinte del bloque para otras formas (esferas, triángulos…).
+    pub fn surface(&self, id: BlockId) -> Surface {
+        let b = self.def(id);
+        Surface { mat_id: b.mat_id, face_tex: Some(b.face_tex), tint: b.tint.map(|(kind, faces)| Tint { color: self.tint_color(kind), faces }) }
+    }
+
+    /// Caja arbitraria con el material y las texturas del bloque.
+    pub fn aabb(&self, id: BlockId, min: Vec3, max: Vec3) -> Aabb {
+        let Surface { mat_id, face_tex, tint } = self.surface(id);
+        let mut aabb = Aabb::new(min, max, mat_id, face_tex);
+        aabb.tint = tint;
+        aabb
+    }
+
+    /// Bloque unitario con esquina en (x, y, z).
+    pub fn cube(&self, id: BlockId, x: f32, y: f32, z: f32) -> Aabb {
+        let min = Vec3::new(x, y, z);
+        self.aabb(id, min, min.add(Vec3::new(1.0, 1.0, 1.0)))
+    }
+
+    /// Geometría del bloque en (x, y, z) según su modelo; vallas y paneles, sin vecinos.
+    pub fn place(&self, id: BlockId, x: f32, y: f32, z: f32, out: &mut Vec<Aabb>) {
+        self.place_linked(id, Vec3::new(x, y, z), 0, out);
+    }
+
+    fn place_linked(&self, id: BlockId, at: Vec3, links: u8, out: &mut Vec<Aabb>) {
+        let (x, y, z) = (at.x, at.y, at.z);
+        match self.def(id).model {
+            Model::Cube => out.push(self.cube(id, x, y, z)),
+            Model::Cross => {
+                // planos sin espesor; la silueta la recorta la textura
+                out.push(self.aabb(id, Vec3::new(x + 0.5, y, z), Vec3::new(x + 0.5, y + 1.0, z + 1.0)));
+                out.push(self.aabb(id, Vec3::new(x, y, z + 0.5), Vec3::new(x + 1.0, y + 1.0, z + 0.5)));
+            }
+            // piezas con las UV del bloque entero: una losa de arriba muestra la mitad de arriba
+            model => out.extend(model.parts(links).into_iter().map(|(lo, hi)| {
+                let mut c = self.aabb(id, at.add(lo), at.add(hi));
+                c.tiled = true;
+                c
+            })),
+        }
+    }
+
+    pub fn shapes(&self) -> Shapes {
+        let parts = self.blocks.iter().enumerate().map(|(id, def)| {
+            let variants = if def.model.connects() { 16 } else { 1 };
+            (0..variants).map(|links| {
+                let mut out = Vec::new();
+                self.place_linked(id, Vec3::new(0.0, 0.0, 0.0), links, &mut out);
+                out
+            }).collect()
+        }).collect();
+        Shapes { parts, models: self.blocks.iter().map(|d| d.model).collect() }
+    }
+
+    /// Traspasa texturas y materiales a la escena.
+    pub fn into_parts(self) -> (Vec<Texture>, Vec<Material>) {
+        (self.textures, self.materials)
+    }
+
+    /// Bloques del diorama, con texturas de `assets/textures`.
+    pub fn minecraft() -> Self {
+        let mut r = Self::new();
+        let dir = "assets/textures";
+        let mut tx = |name: &str, file: &str, kind: TexKind| {
+            r.texture(name, &format!("{}/{}.ppm", dir, file), kind);
+        };
+        tx("grass_side", "grass_side", TexKind::GrassSide);
+        tx("dirt",       "dirt",       TexKind::Dirt);
+        tx("cobble",     "cobble",     TexKind::Cobble);
+        tx("planks",     "planks",     TexKind::Wood);
+        tx("glass",      "glass",      TexKind::Glass);
+        tx("stone",      "piedra",     TexKind::Stone);
+        tx("metal",      "metal",      TexKind::Metal);
+        // césped y hojas en gris: el color lo pone el bioma
+        let still = |file: &str, kind: TexKind| {
+            let path = format!("{}/{}.ppm", dir, file);
+            Texture::from_ppm(&path).unwrap_or_else(|| {
+                eprintln!("aviso: textura '{}' no encontrada en {}, usando procedural", file, path);
+                Texture::new(kind)
+            })
+        };
+        r.add_texture("grass_top", Texture::grayscale(still("grass_top", TexKind::GrassTop), 1.5));
+        r.add_texture("leaves", Texture::grayscale(still("leaves", TexKind::Leaves), 1.6));
+        // agua y lava animadas: tira de cuadros si existe, si no flujo sobre la imagen fija
+        let water = Texture::flow(still("agua", TexKind::Water), (0.05, 0.08), 0.03);
+        r.animated("water", &format!("{}/water_still.ppm", dir), 0.1, water);
+        let lava = Texture::flow(still("lava", TexKind::Lava), (0.01, 0.02), 0.06);
+        r.animated("lava", &format!("{}/lava_still.ppm", dir), 0.15, lava);
+        r.add_texture("log_side", Texture::new(TexKind::Bark));
+        r.add_texture("log_top",  Texture::new(TexKind::LogTop));
+        r.add_texture("sand",     Texture::new(TexKind::Sand));
+        r.add_texture("birch_bark", Texture::new(TexKind::BirchBark));
+        r.add_texture("torch",    Texture::new(TexKind::Torch));
+        r.add_texture("amethyst", Texture::Solid(Color::new(0.62, 0.42, 0.88)));
+        // siluetas para los modelos en cruz
+        r.add_texture("short_grass", Texture::Plant { kind: Plant::Tuft, color: Color::splat(1.0) });
+        r.add_texture("poppy",      Texture::Plant { kind: Plant::Flower, color: Color::new(0.85, 0.1, 0.08) });
+        r.add_texture("dandelion",  Texture::Plant { kind: Plant::Flower, color: Color::new(0.98, 0.85, 0.15) });
+        r.add_texture("cornflower", Texture::Plant { kind: Plant::Flower, color: Color::new(0.3, 0.45, 0.95) });
+        // menas: manchas de color sobre la piedra
+        for (name, color) in [
+            ("coal_ore", Color::new(0.08, 0.08, 0.08)),
+            ("iron_ore", Color::new(0.85, 0.68, 0.55)),
+            ("gold_ore", Color::new(0.98, 0.85, 0.2)),
+            ("diamond_ore", Color::new(0.4, 0.95, 0.9)),
+        ] {
+            r.add_texture(name, Texture::ore(still("piedra", TexKind::Stone), color));
+        }
+
+        let mat = |tex_id: usize, albedo: f32, specular: f32, shininess: f32| Material {
+            tex_id, albedo, specular, transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess, emissive: Color::black(),
+        };
+        let m = mat(r.tex("grass_side"), 1.0, 0.05, 16.0); r.material("grass", m);
+        let m = mat(r.tex("dirt"), 1.0, 0.02, 8.0);        r.material("dirt", m);
+        let m = mat(r.tex("cobble"), 1.0, 0.1, 16.0);      r.material("cobble", m);
+        let m = mat(r.tex("planks"), 1.0, 0.05, 16.0);     r.material("wood", m);
+        let m = mat(r.tex("leaves"), 0.95, 0.05, 8.0);     r.material("leaves", m);
+        let m = Material { transparency: 0.85, reflectivity: 0.05, ior: 1.5, ..mat(r.tex("glass"), 0.98, 0.2, 32.0) };
+        r.material("glass", m);
+        let m = mat(r.tex("stone"), 1.0, 0.1, 16.0);       r.material("stone", m);
+        let m = Material { transparency: 0.9, reflectivity: 0.05, ior: 1.33, ..mat(r.tex("water"), 0.98, 0.5, 32.0) };
+        r.material("water", m);
+        let m = Material { emissive: Color::new(1.8, 0.6, 0.1), ..mat(r.tex("lava"), 1.0, 0.2, 8.0) };
+        r.material("lava", m);
+        let m = Material { reflectivity: 0.7, ..mat(r.tex("metal"), 1.0, 0.9, 64.0) };
+        r.material("metal", m);
+        let m = mat(r.tex("sand"), 1.0, 0.02, 8.0);        r.material("sand", m);
+        let m = mat(r.tex("coal_ore"), 1.0, 0.1, 16.0);    r.material("coal_ore", m);
+        let m = mat(r.tex("iron_ore"), 1.0, 0.3, 32.0);    r.material("iron_ore", m);
+        let m = Material { reflectivity: 0.1, ..mat(r.tex("gold_ore"), 1.0, 0.6, 48.0) };
+        r.material("gold_ore", m);
+        let m = Material { reflectivity: 0.15, ..mat(r.tex("diamond_ore"), 1.0, 0.8, 64.0) };
+        r.material("diamond_ore", m);
+        let m = mat(r.tex("short_grass"), 1.0, 0.02, 8.0); r.material("plant", m);
+        let m = Material { emissive: Color::new(0.9, 0.55, 0.2), ..mat(r.tex("torch"), 1.0, 0.0, 8.0) };
+        r.material("torch", m);
+        let m = Material { reflectivity: 0.25, ..mat(r.tex("amethyst"), 1.0, 0.8, 64.0) };
+        r.material("amethyst", m);
+
+        let grass = r.block("grass", "grass", Faces::Column { top: "grass_top", side: "grass_side", bottom: "dirt" });
+        r.tinted(grass, TintKind::Grass, FACE_TOP);
+        r.block("dirt",   "dirt",   Faces::All("dirt"));
+        r.block("cobble", "cobble", Faces::All("cobble"));
+        r.block("planks", "wood",   Faces::All("planks"));
+        r.block("log",    "wood",   Faces::Column { top: "log_top", side: "log_side", bottom: "log_top" });
+        let leaves = r.block("leaves", "leaves", Faces::All("leaves"));
+        r.tinted(leaves, TintKind::Foliage, ALL_FACES);
+        r.block("glass",  "glass",  Faces::All("glass"));
+        r.block("stone",  "stone",  Faces::All("stone"));
+        r.block("water",  "water",  Faces::All("water"));
+        r.block("lava",   "lava",   Faces::All("lava"));
+        r.block("metal",  "metal",  Faces::All("metal"));
+        r.block("sand",   "sand",   Faces::All("sand"));
+        r.block("amethyst_block", "amethyst", Faces::All("amethyst"));
+        for ore in ["coal_ore", "iron_ore", "gold_ore", "diamond_ore"] {
+            r.block(ore, ore, Faces::All(ore));
+        }
+        r.block("birch_log",  "wood", Faces::Column { top: "log_top", side: "birch_bark", bottom: "log_top" });
+        r.block("spruce_log", "wood", Faces::Column { top: "log_top", side: "log_side", bottom: "log_top" });
+        let birch = r.block("birch_leaves", "leaves", Faces::All("leaves"));
+        r.tinted(birch, TintKind::Birch, ALL_FACES);
+        let spruce = r.block("spruce_leaves", "leaves", Faces::All("leaves"));
+        r.tinted(spruce, TintKind::Spruce, ALL_FACES);
+        let tuft = r.block("short_grass", "plant", Faces::All("short_grass"));
+        r.tinted(tuft, TintKind::Grass, ALL_FACES);
+        r.model(tuft, Model::Cross);
+        for flower in ["poppy", "dandelion", "cornflower"] {
+            let id = r.block(flower, "plant", Faces::All(flower));
+            r.model(id, Model::Cross);
+        }
+        // formas menores que un bloque, con variantes por estado
+        let id = r.block("oak_slab", "wood", Faces::All("planks"));
+        r.model(id, Model::Slab { top: false });
+        let id = r.block("stone_slab", "stone", Faces::All("stone"));
+        r.model(id, Model::Slab { top: false });
+        let id = r.block("oak_stairs", "wood", Faces::All("planks"));
+        r.model(id, Model::Stairs { facing: Facing::North, top: false });
+        let id = r.block("cobble_stairs", "cobble", Faces::All("cobble"));
+        r.model(id, Model::Stairs { facing: Facing::North, top: false });
+        let id = r.block("oak_fence", "wood", Faces::All("planks"));
+        r.model(id, Model::Fence);
+        let id = r.block("glass_pane", "glass", Faces::All("glass"));
+        r.model(id, Model::Pane);
+        let id = r.block("torch", "torch", Faces::All("torch"));
+        r.model(id, Model::Torch { wall: None });
+        let id = r.block("wall_torch", "torch", Faces::All("torch"));
+        r.model(id, Model::Torch { wall: Some(Facing::North) });
+        r
+    }
+}
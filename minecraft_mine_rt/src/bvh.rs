@@ -0,0 +1,119 @@
+use crate::math::{Ray, Vec3};
+
+// Jerarquía de volúmenes envolventes sobre cajas alineadas a los ejes.
+// Solo conoce índices y límites: quien la usa decide cómo intersecar cada primitiva.
+
+const LEAF_SIZE: usize = 4;
+
+struct Node {
+    min: Vec3,
+    max: Vec3,
+    /// Hoja: primer índice en `order`; interno: hijo izquierdo (el derecho es el siguiente)
+    start: u32,
+    /// 0 = nodo interno
+    count: u32,
+}
+
+#[derive(Default)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    order: Vec<u32>,
+    /// Primitivas sin límites finitos (planos infinitos): se prueban siempre
+    always: Vec<u32>,
+}
+
+fn min3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)) }
+fn max3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)) }
+fn axis(v: Vec3, k: usize) -> f32 { [v.x, v.y, v.z][k] }
+
+impl Bvh {
+    /// Construye sobre los límites `(min, max)` de cada primitiva, partiendo por la
+    /// mediana del eje más largo de los centroides.
+    pub fn build(bounds: &[(Vec3, Vec3)]) -> Self {
+        let finite = |(a, b): (Vec3, Vec3)| [a.x, a.y, a.z, b.x, b.y, b.z].iter().all(|v| v.is_finite());
+        let (order, always): (Vec<u32>, Vec<u32>) = (0..bounds.len() as u32).partition(|&i| finite(bounds[i as usize]));
+        let mut bvh = Self { nodes: Vec::with_capacity(order.len() / 2 + 1), order, always };
+        if !bvh.order.is_empty() {
+            let centers: Vec<Vec3> = bounds.iter().map(|(a, b)| a.add(*b).mul(0.5)).collect();
+            bvh.nodes.push(Node { min: Vec3::default(), max: Vec3::default(), start: 0, count: 0 });
+            bvh.split(0, 0, bvh.order.len(), bounds, &centers);
+        }
+        bvh
+    }
+
+    pub fn is_empty(&self) -> bool { self.nodes.is_empty() && self.always.is_empty() }
+
+    fn split(&mut self, node: usize, lo: usize, hi: usize, bounds: &[(Vec3, Vec3)], centers: &[Vec3]) {
+        let items = &mut self.order[lo..hi];
+        let first = bounds[items[0] as usize];
+        let (bmin, bmax) = items.iter().fold(first, |(a, b), &i| (min3(a, bounds[i as usize].0), max3(b, bounds[i as usize].1)));
+        let c0 = centers[items[0] as usize];
+        let (cmin, cmax) = items.iter().fold((c0, c0), |(a, b), &i| (min3(a, centers[i as usize]), max3(b, centers[i as usize])));
+        let ext = cmax.sub(cmin);
+        let k = if ext.x >= ext.y && ext.x >= ext.z { 0 } else if ext.y >= ext.z { 1 } else { 2 };
+
+        self.nodes[node].min = bmin;
+        self.nodes[node].max = bmax;
+        if hi - lo <= LEAF_SIZE || axis(ext, k) <= 0.0 {
+            self.nodes[node].start = lo as u32;
+            self.nodes[node].count = (hi - lo) as u32;
+            return;
+        }
+        let mid = (hi - lo) / 2;
+        items.select_nth_unstable_by(mid, |&a, &b| axis(centers[a as usize], k).total_cmp(&axis(centers[b as usize], k)));
+        let left = self.nodes.len();
+        for _ in 0..2 { self.nodes.push(Node { min: bmin, max: bmax, start: 0, count: 0 }); }
+        self.nodes[node].start = left as u32;
+        self.split(left, lo, lo + mid, bounds, centers);
+        self.split(left + 1, lo + mid, hi, bounds, centers);
+    }
+
+    /// Recorre las primitivas sin límites y luego las cajas que toca el rayo, de cerca a lejos.
+    /// `hit(i)` devuelve la distancia si la primitiva `i` fue alcanzada, y con ella
+    /// se descartan los nodos más lejanos.
+    pub fn visit(&self, ray: &Ray, mut hit: impl FnMut(usize) -> Option<f32>) {
+        let mut t_best = f32::INFINITY;
+        for &i in &self.always {
+            if let Some(t) = hit(i as usize) { t_best = t_best.min(t); }
+        }
+        if self.nodes.is_empty() { return; }
+        let inv = Vec3::new(1.0 / ray.d.x, 1.0 / ray.d.y, 1.0 / ray.d.z);
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0u32);
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n as usize];
+            if slab(ray, inv, node.min, node.max).is_none_or(|t| t > t_best) { continue; }
+            if node.count > 0 {
+                for &i in &self.order[node.start as usize..(node.start + node.count) as usize] {
+                    if let Some(t) = hit(i as usize) { t_best = t_best.min(t); }
+                }
+                continue;
+            }
+            // el hijo más cercano se apila último para visitarlo primero
+            let (l, r) = (node.start, node.start + 1);
+            let tl = slab(ray, inv, self.nodes[l as usize].min, self.nodes[l as usize].max);
+            let tr = slab(ray, inv, self.nodes[r as usize].min, self.nodes[r as usize].max);
+            match (tl, tr) {
+                (Some(a), Some(b)) if a < b => { stack.push(r); stack.push(l); }
+                (Some(_), Some(_)) => { stack.push(l); stack.push(r); }
+                (Some(_), None) => stack.push(l),
+                (None, Some(_)) => stack.push(r),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+/// Distancia de entrada a la caja (0 si el origen está dentro), o `None` si no la toca.
+fn slab(ray: &Ray, inv: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let (t1, t2) = ((min.x - ray.o.x) * inv.x, (max.x - ray.o.x) * inv.x);
+    let (mut tmin, mut tmax) = (t1.min(t2), t1.max(t2));
+    let (t1, t2) = ((min.y - ray.o.y) * inv.y, (max.y - ray.o.y) * inv.y);
+    tmin = tmin.max(t1.min(t2));
+    tmax = tmax.min(t1.max(t2));
+    let (t1, t2) = ((min.z - ray.o.z) * inv.z, (max.z - ray.o.z) * inv.z);
+    tmin = tmin.max(t1.min(t2));
+    tmax = tmax.min(t1.max(t2));
+    // margen para no perder cajas planas (agua, caminos) por redondeo
+    if tmax + 1e-4 >= tmin.max(0.0) { Some(tmin.max(0.0)) } else { None }
+}
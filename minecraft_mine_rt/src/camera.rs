@@ -1,23 +1,234 @@
+use crate::aabb::Aabb;
 use crate::math::{Vec3, Ray};
 
+/// Lente delgada. Sin apertura la cámara es estenopeica y todo queda enfocado.
+#[derive(Clone, Copy, Default)]
+pub struct Lens {
+    /// Diámetro de la apertura en unidades del mundo
+    pub aperture: f32,
+    /// Distancia al plano enfocado; `None` enfoca el objetivo de la cámara
+    pub focus: Option<f32>,
+    /// Hojas del diafragma: 0 = circular, 3 o más = polígono (la forma del bokeh)
+    pub blades: u32,
+    /// Descentramiento de la imagen en fracciones del alto (x a la derecha, y arriba):
+    /// encuadra más arriba sin inclinar la cámara, y las verticales siguen verticales
+    pub shift: (f32, f32),
+    /// Basculamiento del plano de foco en grados alrededor del eje horizontal; con
+    /// apertura, el efecto de maqueta (positivo: el foco se aleja hacia arriba)
+    pub tilt: f32,
+}
+
+impl Lens {
+    /// Punto de la apertura (radio 1) a partir de `(u, v)` uniformes en [0, 1).
+    fn sample(&self, u: f32, v: f32) -> (f32, f32) {
+        if self.blades < 3 {
+            let (r, a) = (u.sqrt(), v * std::f32::consts::TAU);
+            return (r * a.cos(), r * a.sin());
+        }
+        // un triángulo del polígono (centro y dos vértices) y un punto uniforme en él
+        let n = self.blades as f32;
+        let k = (u * n).floor();
+        let (s, t) = ((u * n - k).sqrt(), v);
+        let corner = |i: f32| {
+            let a = std::f32::consts::FRAC_PI_2 + i * std::f32::consts::TAU / n;
+            (a.cos(), a.sin())
+        };
+        let (a, b) = (corner(k), corner(k + 1.0));
+        (s * ((1.0 - t) * a.0 + t * b.0), s * ((1.0 - t) * a.1 + t * b.1))
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub enum Projection {
+    #[default]
+    Perspective,
+    /// Rayos paralelos; `height` es el alto de la vista en bloques (`None`: lo que la
+    /// perspectiva vería a la distancia del objetivo)
+    Orthographic { height: Option<f32> },
+    /// Ortográfica desde la diagonal en planta más cercana al ojo, a `elevation` grados
+    /// sobre el horizonte y a la misma distancia del objetivo
+    Axonometric { height: Option<f32>, elevation: f32 },
+    /// Panorama de 360° × 180° (imagen 2:1) alrededor del ojo, con el objetivo en el
+    /// centro y el horizonte perpendicular a `up`; sin lente
+    Equirectangular,
+}
+
+impl Projection {
+    /// `perspective`, `orthographic`, `isometric` (los tres ejes a 120°), `dimetric`
+    /// (la 2:1 de los juegos de píxeles) o `equirectangular`.
+    pub fn by_name(name: &str, height: Option<f32>) -> Option<Self> {
+        Some(match name {
+            "perspective" => Projection::Perspective,
+            "orthographic" => Projection::Orthographic { height },
+            "isometric" => Projection::Axonometric { height, elevation: (0.5f32).sqrt().atan().to_degrees() },
+            // la pendiente en pantalla de las diagonales del suelo es sen(elevación): 1/2 a 30°
+            "dimetric" => Projection::Axonometric { height, elevation: (0.5f32).asin().to_degrees() },
+            "equirectangular" => Projection::Equirectangular,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
 pub struct Camera {
     pub eye: Vec3, pub target: Vec3, pub up: Vec3,
-    pub fov_deg: f32, pub aspect: f32
+    pub fov_deg: f32, pub aspect: f32,
+    pub lens: Lens,
+    pub projection: Projection,
 }
 impl Camera {
     pub fn new(eye:Vec3, target:Vec3, up:Vec3, fov_deg:f32, aspect:f32)->Self{
-        Self{eye,target,up,fov_deg,aspect}
+        Self{eye,target,up,fov_deg,aspect,lens:Lens::default(),projection:Projection::default()}
+    }
+
+    /// Pose intermedia entre esta y `other` (`u` de 0 a 1); la lente y la proyección son las de esta.
+    pub fn lerp(&self, other:&Camera, u:f32)->Camera{
+        let mix = |a:Vec3, b:Vec3| a.add(b.sub(a).mul(u));
+        Camera{ eye:mix(self.eye, other.eye), target:mix(self.target, other.target), up:mix(self.up, other.up),
+                fov_deg:self.fov_deg + (other.fov_deg - self.fov_deg)*u, ..*self }
     }
-    pub fn ray_for(&self, x:usize, y:usize, w:usize, h:usize)->Ray{
-        let fov = (self.fov_deg.to_radians()*0.5).tan();
-        let px = ( ( (x as f32 + 0.5)/w as f32 )*2.0 - 1.0 ) * self.aspect * fov;
-        let py = ( 1.0 - ( (y as f32 + 0.5)/h as f32 )*2.0 ) * fov;
 
-        let fwd = self.target.sub(self.eye).norm();
+    /// Ojo efectivo (el de las vistas axonométricas se mueve a su diagonal).
+    pub fn view_eye(&self)->Vec3{
+        let Projection::Axonometric{ elevation, .. } = self.projection else { return self.eye };
+        let d = self.eye.sub(self.target);
+        let side = |v: f32| if v < 0.0 { -1.0 } else { 1.0 };
+        let (c, s) = (elevation.to_radians().cos(), elevation.to_radians().sin());
+        let dir = Vec3::new(side(d.x) * c * std::f32::consts::FRAC_1_SQRT_2, s, side(d.z) * c * std::f32::consts::FRAC_1_SQRT_2);
+        self.target.add(dir.mul(d.len()))
+    }
+    /// Rayo por el punto `(px, py)` de la imagen en píxeles (`x + 0.5` es el centro) y
+    /// por el punto `lens` de la apertura, con `lens` uniforme en [0, 1)².
+    pub fn ray_for(&self, px:f32, py:f32, w:usize, h:usize, lens:(f32,f32))->Ray{
+        let eye = self.view_eye();
+        let fwd = self.target.sub(eye).norm();
         let right = fwd.cross(self.up).norm();
         let upv = right.cross(fwd).norm();
 
-        let dir = right.mul(px).add(upv.mul(py)).add(fwd).norm();
-        Ray{ o:self.eye, d:dir }
+        if let Projection::Equirectangular = self.projection {
+            // longitud desde el objetivo hacia la derecha, latitud desde el horizonte
+            let up = self.up.norm();
+            let ahead = up.cross(right);
+            let lon = ( px/w as f32 - 0.5 ) * std::f32::consts::TAU;
+            let lat = ( 0.5 - py/h as f32 ) * std::f32::consts::PI;
+            let flat = ahead.mul(lon.cos()).add(right.mul(lon.sin()));
+            return Ray{ o:eye, d:flat.mul(lat.cos()).add(up.mul(lat.sin())).norm(), time:0.0 };
+        }
+
+        // medio alto de la vista: en tangente para la perspectiva, en bloques para las paralelas
+        let dist = self.target.sub(eye).len();
+        let tan = (self.fov_deg.to_radians()*0.5).tan();
+        let (half, parallel) = match self.projection {
+            Projection::Perspective | Projection::Equirectangular => (tan, false),
+            Projection::Orthographic{ height } | Projection::Axonometric{ height, .. } =>
+                (height.map_or(dist * tan, |h| h * 0.5), true),
+        };
+        let sx = ( ( ( px/w as f32 )*2.0 - 1.0 ) * self.aspect + self.lens.shift.0 * 2.0 ) * half;
+        let sy = ( 1.0 - ( py/h as f32 )*2.0 + self.lens.shift.1 * 2.0 ) * half;
+
+        // origen y dirección del rayo central, con la componente de `fwd` igual a 1
+        let offset = right.mul(sx).add(upv.mul(sy));
+        let (o, dir) = if parallel { (eye.add(offset), fwd) } else { (eye, offset.add(fwd)) };
+        if self.lens.aperture <= 0.0 { return Ray{ o, d:dir.norm(), time:0.0 }; }
+
+        // todo lo que está en el plano de foco queda nítido; sin basculamiento, a
+        // `focus` a lo largo de la vista
+        let focus = self.lens.focus.unwrap_or(dist);
+        let t = self.lens.tilt.to_radians();
+        let n = fwd.mul(t.cos()).sub(upv.mul(t.sin()));
+        let along = eye.add(fwd.mul(focus)).sub(o).dot(n) / dir.dot(n);
+        let sharp = o.add(dir.mul(along));
+        let (lx, ly) = self.lens.sample(lens.0, lens.1);
+        let r = self.lens.aperture * 0.5;
+        let o = o.add(right.mul(lx * r)).add(upv.mul(ly * r));
+        Ray{ o, d:sharp.sub(o).norm(), time:0.0 }
+    }
+}
+
+/// Dos vistas de la misma cámara para 3D: cámaras paralelas separadas `ipd` a lo
+/// largo de la derecha y descentradas para coincidir a `convergence` (sin el
+/// paralaje vertical que daría girarlas hacia dentro). Pensado para la perspectiva.
+#[derive(Clone, Copy)]
+pub struct Stereo {
+    pub layout: Layout,
+    /// Distancia entre los ojos; `None` = 1/30 de la convergencia
+    pub ipd: Option<f32>,
+    /// Distancia que queda en el plano de la pantalla; `None` = la del objetivo
+    pub convergence: Option<f32>,
+}
+
+/// Cómo se juntan las dos vistas en un cuadro.
+#[derive(Clone, Copy)]
+pub enum Layout {
+    /// Izquierda a la izquierda, cada una con la mitad del ancho
+    SideBySide,
+    /// Izquierda arriba, cada una con la mitad del alto
+    OverUnder,
+    /// Rojo del ojo izquierdo, verde y azul del derecho
+    Anaglyph,
+}
+
+impl Layout {
+    pub fn by_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "side-by-side" => Layout::SideBySide,
+            "over-under" => Layout::OverUnder,
+            "anaglyph" => Layout::Anaglyph,
+            _ => return None,
+        })
+    }
+}
+
+impl Stereo {
+    /// Cámaras izquierda y derecha con el aspecto `aspect` de cada vista.
+    pub fn eyes(&self, cam: &Camera, aspect: f32) -> [Camera; 2] {
+        let eye = cam.view_eye();
+        let convergence = self.convergence.unwrap_or_else(|| cam.target.sub(eye).len());
+        let ipd = self.ipd.unwrap_or(convergence / 30.0);
+        let right = cam.target.sub(eye).cross(cam.up).norm();
+        // un punto a `convergence` en el eje se ve a ±ipd/2 de cada ojo; el
+        // descentramiento (en fracciones del alto) lo lleva al centro de la imagen
+        let shift = ipd / (4.0 * convergence * (cam.fov_deg.to_radians() * 0.5).tan());
+        [-0.5f32, 0.5].map(|side| {
+            let mut c = *cam;
+            let offset = right.mul(side * ipd);
+            (c.eye, c.target, c.aspect) = (cam.eye.add(offset), cam.target.add(offset), aspect);
+            c.lens.shift.0 -= side * 2.0 * shift;
+            c
+        })
+    }
+}
+
+/// Corte transversal: quita lo que queda entre la cámara y el plano `eje = at`
+/// para ver el interior del terreno (cuevas, vetas).
+#[derive(Clone, Copy)]
+pub struct Section {
+    pub axis: usize, // 0:x, 1:y, 2:z
+    pub at: f32,
+}
+
+impl Section {
+    /// Formato `x=12`, `y=5` o `z=20`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (axis, at) = s.split_once('=')?;
+        let axis = match axis.trim() { "x" => 0, "y" => 1, "z" => 2, _ => return None };
+        Some(Self { axis, at: at.trim().parse().ok()? })
+    }
+
+    /// Recorta las cajas al semiespacio opuesto a `eye`.
+    pub fn apply(&self, cubes: &mut Vec<Aabb>, eye: Vec3) {
+        let get = |v: Vec3| [v.x, v.y, v.z][self.axis];
+        let set = |v: &mut Vec3, x: f32| match self.axis { 0 => v.x = x, 1 => v.y = x, _ => v.z = x };
+        let keep_below = get(eye) > self.at;
+        cubes.retain_mut(|c| {
+            if keep_below {
+                if get(c.min) >= self.at { return false; }
+                if get(c.max) > self.at { set(&mut c.max, self.at); }
+            } else {
+                if get(c.max) <= self.at { return false; }
+                if get(c.min) < self.at { set(&mut c.min, self.at); }
+            }
+            true
+        });
     }
 }
@@ -0,0 +1,235 @@
+use crate::biome::Biome;
+use crate::camera::{Layout, Lens, Projection, Section, Stereo};
+use crate::exr::{Compression, Pixel};
+use crate::terrain::Params;
+use crate::tonemap::ToneMap;
+use crate::world::MAX_BLOCKS;
+
+pub const USAGE: &str = "\
+uso: minecraft_mine_rt [opciones]
+  --size WxH        resolución (640x360)
+  --frames N        cuadros de la vuelta completa (180)
+  --frame K         renderiza solo el cuadro K
+  --biome NOMBRE    plains, forest, jungle, savanna, taiga, swamp (plains)
+  --scene ARCHIVO   carga la escena de un archivo .toml en vez del diorama
+  --seed N          genera un paisaje con esa semilla en vez del diorama
+  --world WxD       tamaño del paisaje generado (48x48)
+  --section EJE=N   corte transversal (x=24, z=10…): quita lo que tapa el interior
+  --camera ARCHIVO  recorrido de cámara ([camera] y [[keyframe]] de un .toml) en vez
+                    de la órbita; el tiempo de las claves va a 30 cuadros por segundo
+  --samples N       rayos por píxel (1; 16 con apertura): suaviza bordes y desenfoque
+  --aperture A      diámetro de la lente en bloques: desenfoca lo que no está a foco
+  --focus D         distancia enfocada (la del objetivo de la cámara)
+  --blades N        hojas del diafragma, 3 o más: bokeh poligonal (circular)
+  --shutter A,B     obturador abierto de A a B en fracciones del cuadro (0,0.5):
+                    desenfoque de movimiento de la cámara y las cajas con velocidad
+  --tilt GRADOS     bascula el plano de foco; negativo y con apertura, efecto maqueta
+  --shift X,Y       descentra la imagen (fracciones del alto): encuadre sin inclinar
+  --projection P    perspective, orthographic, isometric, dimetric o equirectangular
+                    (panorama 360°, con --size 2:1) (perspective)
+  --view-height H   alto de la vista ortográfica en bloques (el que encuadra el objetivo)
+  --stereo MODO     side-by-side, over-under o anaglyph (rojo-cian): las dos vistas
+                    en un cuadro del tamaño de --size
+  --ipd D           distancia entre los ojos en bloques (1/30 de la convergencia)
+  --convergence D   distancia que queda en el plano de la pantalla (la del objetivo)
+  --format F        ppm, pfm o exr: los cuadros en out/ (ppm); pfm y exr guardan el
+                    color lineal sin recortar; exr, sin el cielo y con alfa
+                    (cobertura) premultiplicado
+  --compression C   none o zip para exr (zip)
+  --pixel P         half o float para exr (half)
+  --tonemap T       clamp, reinhard o aces para las salidas de 8 bits (clamp)
+  --exposure EV     pasos de exposición antes del mapeo de tonos (0)
+  --cubemap CARPETA guarda un cubemap desde el ojo (posx.ppm, negx.ppm… de lado el
+                    alto de --size) para usarlo como skybox y termina; con --frame
+                    el de ese cuadro, si no el primero
+  --export ARCHIVO  guarda las caras visibles en .obj (+ .mtl y PNG) o .glb y termina;
+                    con --frame exporta ese cuadro, si no el primero";
+
+/// Formato de los cuadros de `out/`.
+#[derive(Clone, Copy)]
+pub enum Format {
+    Ppm,
+    Pfm,
+    Exr(Compression, Pixel),
+}
+
+pub struct Options {
+    pub width: usize,
+    pub height: usize,
+    pub frames: usize,
+    pub only_frame: Option<usize>,
+    pub biome: Biome,
+    pub scene: Option<String>,
+    /// Paisaje generado (`--seed`) en lugar del diorama
+    pub terrain: Option<Params>,
+    pub section: Option<Section>,
+    /// Archivo con el recorrido de cámara (solo [camera] y [[keyframe]])
+    pub camera: Option<String>,
+    /// Rayos por píxel; sin valor, 1 o 16 si la lente tiene apertura
+    pub samples: Option<usize>,
+    /// Apertura y cierre del obturador en fracciones de cuadro
+    pub shutter: Option<(f32, f32)>,
+    /// Lente de `--aperture`, `--focus`, `--blades`, `--tilt` y `--shift`; reemplaza la de la escena
+    pub lens: Option<Lens>,
+    /// `--projection` y `--view-height`; reemplaza la de la escena
+    pub projection: Option<Projection>,
+    /// Render estéreo de `--stereo`, `--ipd` y `--convergence`
+    pub stereo: Option<Stereo>,
+    pub format: Format,
+    /// Mapeo de tonos y exposición (en pasos) de los PPM
+    pub tone: ToneMap,
+    pub exposure: f32,
+    /// Carpeta donde guardar las seis caras de un cubemap en vez del cuadro
+    pub cubemap: Option<String>,
+    /// Archivo `.obj` o `.glb` donde exportar la escena en vez de renderizarla
+    pub export: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self { width: 640, height: 360, frames: 180, only_frame: None, biome: Biome::new(0.8, 0.4), scene: None, terrain: None, section: None, camera: None, samples: None, shutter: None, lens: None, projection: None, stereo: None, format: Format::Ppm, tone: ToneMap::Clamp, exposure: 0.0, cubemap: None, export: None }
+    }
+}
+
+impl Options {
+    pub fn from_args() -> Result<Self, String> {
+        parse(std::env::args().skip(1))
+    }
+}
+
+fn parse(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut o = Options::default();
+    let (mut projection, mut height) = (None, None);
+    let (mut layout, mut ipd, mut convergence) = (None, None, None);
+    let (mut format, mut compression, mut pixel) = (None, None, None);
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("falta el valor de {}", flag));
+        match flag.as_str() {
+            "--size" => {
+                let v = value()?;
+                let (w, h) = v.split_once('x').ok_or_else(|| format!("tamaño inválido: {}", v))?;
+                o.width = number(w)?;
+                o.height = number(h)?;
+                if o.width == 0 || o.height == 0 { return Err(format!("tamaño vacío: {}", v)); }
+            }
+            "--frames" => o.frames = number(&value()?)?.max(2),
+            "--frame" => o.only_frame = Some(number(&value()?)?),
+            "--biome" => {
+                let v = value()?;
+                o.biome = Biome::by_name(&v).ok_or_else(|| format!("bioma desconocido: {}", v))?;
+            }
+            "--scene" => o.scene = Some(value()?),
+            "--camera" => o.camera = Some(value()?),
+            "--seed" => {
+                let v = value()?;
+                let seed = v.trim().parse().map_err(|_| format!("semilla inválida: {}", v))?;
+                o.terrain.get_or_insert_with(Params::default).seed = seed;
+            }
+            "--world" => {
+                let v = value()?;
+                let (w, d) = v.split_once('x').ok_or_else(|| format!("tamaño inválido: {}", v))?;
+                let p = o.terrain.get_or_insert_with(Params::default);
+                let (w, d) = (number(w)?, number(d)?);
+                if w == 0 || d == 0 { return Err(format!("tamaño vacío: {}", v)); }
+                if w.checked_mul(d).and_then(|n| n.checked_mul(p.height as usize)).is_none_or(|n| n > MAX_BLOCKS) {
+                    return Err(format!("paisaje demasiado grande: {}", v));
+                }
+                p.size = [w as i32, d as i32];
+            }
+            "--section" => {
+                let v = value()?;
+                o.section = Some(Section::parse(&v).ok_or_else(|| format!("corte inválido: {} (x=N, y=N o z=N)", v))?);
+            }
+            "--samples" => o.samples = Some(number(&value()?)?.max(1)),
+            "--aperture" => o.lens.get_or_insert_with(Lens::default).aperture = real(&value()?)?.max(0.0),
+            "--focus" => o.lens.get_or_insert_with(Lens::default).focus = Some(real(&value()?)?),
+            "--blades" => {
+                let n = number(&value()?)?;
+                if !(3..=32).contains(&n) { return Err(format!("--blades va de 3 a 32: {}", n)); }
+                o.lens.get_or_insert_with(Lens::default).blades = n as u32;
+            }
+            "--shutter" => {
+                let v = value()?;
+                let (a, b) = v.split_once(',').ok_or_else(|| format!("obturador inválido: {} (A,B)", v))?;
+                let (a, b) = (real(a)?, real(b)?);
+                if b < a { return Err(format!("el obturador cierra antes de abrir: {}", v)); }
+                o.shutter = Some((a, b));
+            }
+            "--tilt" => o.lens.get_or_insert_with(Lens::default).tilt = real(&value()?)?,
+            "--shift" => {
+                let v = value()?;
+                let (x, y) = v.split_once(',').ok_or_else(|| format!("desplazamiento inválido: {} (X,Y)", v))?;
+                o.lens.get_or_insert_with(Lens::default).shift = (real(x)?, real(y)?);
+            }
+            "--projection" => projection = Some(value()?),
+            "--view-height" => {
+                let h = real(&value()?)?;
+                if h <= 0.0 { return Err(format!("--view-height debe ser mayor que 0: {}", h)); }
+                height = Some(h);
+            }
+            "--stereo" => {
+                let v = value()?;
+                layout = Some(Layout::by_name(&v).ok_or_else(|| format!("modo estéreo desconocido: {} (side-by-side, over-under o anaglyph)", v))?);
+            }
+            "--ipd" => ipd = Some(real(&value()?)?),
+            "--convergence" => convergence = Some(real(&value()?)?),
+            "--format" => format = Some(value()?),
+            "--compression" => {
+                let v = value()?;
+                compression = Some(Compression::by_name(&v).ok_or_else(|| format!("compresión desconocida: {} (none o zip)", v))?);
+            }
+            "--pixel" => {
+                let v = value()?;
+                pixel = Some(Pixel::by_name(&v).ok_or_else(|| format!("tipo de píxel desconocido: {} (half o float)", v))?);
+            }
+            "--tonemap" => {
+                let v = value()?;
+                o.tone = ToneMap::by_name(&v).ok_or_else(|| format!("mapeo de tonos desconocido: {} (clamp, reinhard o aces)", v))?;
+            }
+            "--exposure" => o.exposure = real(&value()?)?,
+            "--cubemap" => o.cubemap = Some(value()?),
+            "--export" => {
+                let v = value()?;
+                let ext = v.rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase());
+                if !matches!(ext.as_deref(), Some("obj" | "glb")) { return Err(format!("--export necesita un archivo .obj o .glb: {}", v)); }
+                o.export = Some(v);
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => return Err(format!("opción desconocida: {}\n{}", flag, USAGE)),
+        }
+    }
+    // el alto solo no basta: implica la ortográfica
+    o.projection = match projection {
+        Some(name) => Some(Projection::by_name(&name, height).ok_or_else(|| format!("proyección desconocida: {}", name))?),
+        None => height.map(|h| Projection::Orthographic { height: Some(h) }),
+    };
+    o.format = match format.as_deref() {
+        None | Some("ppm") => Format::Ppm,
+        Some("pfm") => Format::Pfm,
+        Some("exr") => Format::Exr(compression.unwrap_or(Compression::Zip), pixel.unwrap_or(Pixel::Half)),
+        Some(f) => return Err(format!("formato desconocido: {} (ppm, pfm o exr)", f)),
+    };
+    if (compression.is_some() || pixel.is_some()) && !matches!(o.format, Format::Exr(..)) {
+        return Err("--compression y --pixel son para --format exr".to_string());
+    }
+    o.stereo = match layout {
+        Some(layout) => Some(Stereo { layout, ipd, convergence }),
+        None if ipd.is_some() || convergence.is_some() => return Err("--ipd y --convergence necesitan --stereo".to_string()),
+        None => None,
+    };
+    // cada vista se queda con la mitad del ancho o del alto: al menos un píxel
+    match layout {
+        Some(Layout::SideBySide) if o.width < 2 => return Err(format!("--stereo side-by-side necesita al menos 2 píxeles de ancho: {}", o.width)),
+        Some(Layout::OverUnder) if o.height < 2 => return Err(format!("--stereo over-under necesita al menos 2 píxeles de alto: {}", o.height)),
+        _ => {}
+    }
+    Ok(o)
+}
+
+fn number(s: &str) -> Result<usize, String> {
+    s.trim().parse().map_err(|_| format!("número inválido: {}", s))
+}
+
+fn real(s: &str) -> Result<f32, String> {
+    s.trim().parse().map_err(|_| format!("número inválido: {}", s))
+}
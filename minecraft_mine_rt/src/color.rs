@@ -4,7 +4,6 @@ pub struct Color { pub r:f32, pub g:f32, pub b:f32 }
 impl Color {
     pub fn new(r:f32,g:f32,b:f32)->Self{Self{r,g,b}}
     pub fn black()->Self{Self::new(0.0,0.0,0.0)}
-    pub fn white()->Self{Self::new(1.0,1.0,1.0)}
     pub fn from_u8(r:u8,g:u8,b:u8)->Self{
         Self::new(r as f32/255.0, g as f32/255.0, b as f32/255.0)
     }
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::aabb::Aabb;
+use crate::color::Color;
+use crate::math::Vec3;
+use crate::merge;
+use crate::png;
+use crate::renderer::Scene;
+use crate::texture::Texture;
+
+// Exportación de los cubos de la escena para otras herramientas: solo las caras que
+// se ven, con la textura y las UV de cada cara, a Wavefront (.obj + .mtl + PNG) o a
+// glTF 2.0 binario (.glb) con las texturas dentro. Las procedurales y animadas se
+// hornean al tiempo de la escena y el tinte de bioma queda pintado en la imagen.
+
+/// Lado de las texturas que no son imágenes (procedurales, colores lisos).
+const BAKE: usize = 16;
+
+const NORMALS: [[f32; 3]; 6] = [[-1.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, -1.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -1.0], [0.0, 0.0, 1.0]];
+
+/// Aspecto de una cara: material, textura y tinte (bits del color).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Look {
+    mat_id: usize,
+    tex_id: usize,
+    tint: Option<[u32; 3]>,
+}
+
+struct Quad {
+    /// Esquinas en sentido antihorario vistas desde fuera
+    p: [[f32; 3]; 4],
+    uv: [(f32, f32); 4],
+    /// 0:-X, 1:+X, 2:-Y, 3:+Y, 4:-Z, 5:+Z
+    face: usize,
+}
+
+pub struct Stats {
+    pub faces: usize,
+    pub materials: usize,
+}
+
+fn arr(v: Vec3) -> [f32; 3] { [v.x, v.y, v.z] }
+
+/// `lo..hi` partido en los enteros intermedios: las cajas fusionadas se tapan por bloques.
+fn cuts(lo: f32, hi: f32) -> Vec<f32> {
+    let mut c = vec![lo];
+    c.extend((lo.floor() as i32 + 1..hi.ceil() as i32).map(|i| i as f32).filter(|&x| x > lo + 1e-4 && x < hi - 1e-4));
+    c.push(hi);
+    c
+}
+
+/// UV como en `intersect_aabb` pero sin envolver: el mosaico lo repite el muestreo.
+fn face_uv(c: &Aabb, face: usize, p: [f32; 3]) -> (f32, f32) {
+    let (lo, hi) = (arr(c.min), arr(c.max));
+    let lin = |k: usize| if c.tiled { p[k] } else { (p[k] - lo[k]) / (hi[k] - lo[k]) };
+    let rev = |k: usize| if c.tiled { -p[k] } else { (hi[k] - p[k]) / (hi[k] - lo[k]) };
+    match face {
+        0 => (lin(2), lin(1)),
+        1 => (rev(2), lin(1)),
+        2 => (lin(0), lin(2)),
+        3 => (lin(0), rev(2)),
+        4 => (lin(0), lin(1)),
+        _ => (rev(0), lin(1)),
+    }
+}
+
+/// Caras visibles agrupadas por aspecto, en orden de aparición. Un trozo de cara se
+/// descarta si lo tapa entero un cubo opaco o uno igual (vidrio contra vidrio); las
+/// cajas sin espesor (plantas en cruz) dan una sola cara, vista por los dos lados.
+fn visible_faces(scene: &Scene) -> Vec<(Look, Vec<Quad>)> {
+    let cubes = &scene.cubes;
+    let opaque: Vec<bool> = cubes.iter().map(|c| merge::opaque(c, &scene.materials, &scene.textures)).collect();
+    // celda de la rejilla -> cubos con volumen que la ocupan
+    let mut grid: HashMap<[i32; 3], Vec<usize>> = HashMap::new();
+    for (i, c) in cubes.iter().enumerate() {
+        let (lo, hi) = (arr(c.min), arr(c.max));
+        if (0..3).any(|k| hi[k] - lo[k] <= 1e-4) { continue; }
+        let r = |k: usize| lo[k].floor() as i32..hi[k].ceil() as i32;
+        for x in r(0) {
+            for y in r(1) {
+                for z in r(2) { grid.entry([x, y, z]).or_default().push(i); }
+            }
+        }
+    }
+
+    let mut groups: Vec<(Look, Vec<Quad>)> = Vec::new();
+    let mut index: HashMap<Look, usize> = HashMap::new();
+    for (i, c) in cubes.iter().enumerate() {
+        let (lo, hi) = (arr(c.min), arr(c.max));
+        for face in 0..6 {
+            let (k, pos) = (face / 2, face % 2 == 1);
+            let (a, b) = ((k + 1) % 3, (k + 2) % 3);
+            if hi[a] - lo[a] <= 1e-4 || hi[b] - lo[b] <= 1e-4 || (pos && hi[k] - lo[k] <= 1e-4) { continue; }
+            let plane = if pos { hi[k] } else { lo[k] };
+            let covered = |[a0, a1, b0, b1]: [f32; 4]| {
+                let mut q = [0.0; 3];
+                q[k] = plane + if pos { 1e-3 } else { -1e-3 };
+                q[a] = (a0 + a1) * 0.5;
+                q[b] = (b0 + b1) * 0.5;
+                grid.get(&q.map(|v| v.floor() as i32)).is_some_and(|list| list.iter().any(|&j| {
+                    let (o, olo, ohi) = (&cubes[j], arr(cubes[j].min), arr(cubes[j].max));
+                    j != i
+                        && (opaque[j] || (o.mat_id == c.mat_id && o.face_tex == c.face_tex))
+                        && olo[k] <= q[k] && q[k] <= ohi[k]
+                        && olo[a] <= a0 + 1e-4 && ohi[a] >= a1 - 1e-4
+                        && olo[b] <= b0 + 1e-4 && ohi[b] >= b1 - 1e-4
+                }))
+            };
+            let (ca, cb) = (cuts(lo[a], hi[a]), cuts(lo[b], hi[b]));
+            let all = (ca.len() - 1) * (cb.len() - 1);
+            let mut parts: Vec<[f32; 4]> = ca.windows(2)
+                .flat_map(|wa| cb.windows(2).map(move |wb| [wa[0], wa[1], wb[0], wb[1]]))
+                .filter(|&r| !covered(r))
+                .collect();
+            if parts.is_empty() { continue; }
+            // sin nada encima, la cara entera en un solo cuadrilátero
+            if parts.len() == all { parts = vec![[lo[a], hi[a], lo[b], hi[b]]]; }
+
+            let tex_id = c.face_tex.map_or(scene.materials[c.mat_id].tex_id, |t| t[face]);
+            let tint = c.tint.filter(|t| t.faces & (1 << face) != 0).map(|t| [t.color.r.to_bits(), t.color.g.to_bits(), t.color.b.to_bits()]);
+            let look = Look { mat_id: c.mat_id, tex_id, tint };
+            let g = *index.entry(look).or_insert_with(|| {
+                groups.push((look, Vec::new()));
+                groups.len() - 1
+            });
+            for [a0, a1, b0, b1] in parts {
+                // e_a × e_b = e_k: antihorario en la cara positiva, al revés en la negativa
+                let corners = if pos { [(a0, b0), (a1, b0), (a1, b1), (a0, b1)] } else { [(a0, b0), (a0, b1), (a1, b1), (a1, b0)] };
+                let p = corners.map(|(u, v)| {
+                    let mut p = [0.0; 3];
+                    p[k] = plane;
+                    p[a] = u;
+                    p[b] = v;
+                    p
+                });
+                groups[g].1.push(Quad { p, uv: p.map(|p| face_uv(c, face, p)), face });
+            }
+        }
+    }
+    groups
+}
+
+fn cutout(scene: &Scene, look: Look) -> bool { matches!(scene.textures[look.tex_id], Texture::Plant { .. }) }
+
+/// Tamaño natural de una textura: el de su imagen, o `BAKE` si es procedural.
+fn size(t: &Texture) -> (usize, usize) {
+    match t {
+        Texture::Image { w, h, .. } | Texture::Animated { w, h, .. } => (*w, *h),
+        Texture::Flow { inner, .. } | Texture::Gray { inner, .. } | Texture::Ore { inner, .. } => size(inner),
+        _ => (BAKE, BAKE),
+    }
+}
+
+/// PNG de la textura de `look` al tiempo de la escena, con el tinte y con la misma
+/// gamma que el render; las siluetas llevan alfa.
+fn bake(scene: &Scene, look: Look) -> Vec<u8> {
+    let tex = &scene.textures[look.tex_id];
+    let (w, h) = size(tex);
+    let alpha = cutout(scene, look);
+    let tint = look.tint.map_or(Color::splat(1.0), |[r, g, b]| Color::new(f32::from_bits(r), f32::from_bits(g), f32::from_bits(b)));
+    let channels = if alpha { 4 } else { 3 };
+    let mut px = Vec::with_capacity(w * h * channels);
+    for y in 0..h {
+        for x in 0..w {
+            let (u, v) = ((x as f32 + 0.5) / w as f32, 1.0 - (y as f32 + 0.5) / h as f32);
+            px.extend(tex.sample(u, v, scene.time).hadamard(tint).to_u8_gamma());
+            if alpha { px.push(if tex.covers(u, v) { 255 } else { 0 }); }
+        }
+    }
+    png::encode(w, h, channels, &px)
+}
+
+/// Escribe los cubos visibles de `scene` en `path`: `.obj` (con su `.mtl` y las
+/// texturas PNG al lado) o `.glb`. Las instancias y las formas no se exportan.
+pub fn write(path: &str, scene: &Scene) -> io::Result<Stats> {
+    let groups = visible_faces(scene);
+    let faces = groups.iter().map(|g| g.1.len()).sum();
+    if faces == 0 { return Err(io::Error::other("no hay caras visibles que exportar")); }
+    let p = Path::new(path);
+    match p.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+        Some("obj") => write_obj(p, scene, &groups)?,
+        Some("glb") => write_glb(p, scene, &groups)?,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "la extensión debe ser .obj o .glb")),
+    }
+    Ok(Stats { faces, materials: groups.len() })
+}
+
+fn write_obj(path: &Path, scene: &Scene, groups: &[(Look, Vec<Quad>)]) -> io::Result<()> {
+    let stem = path.file_stem().map_or("escena".to_string(), |s| s.to_string_lossy().into_owned());
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut obj = format!("# minecraft_mine_rt: caras visibles de la escena\nmtllib {}.mtl\n", stem);
+    for [x, y, z] in NORMALS { obj += &format!("vn {} {} {}\n", x, y, z); }
+    let mut mtl = String::new();
+    let mut images: HashMap<(usize, Option<[u32; 3]>), String> = HashMap::new();
+    let mut nv = 0;
+    for (g, (look, quads)) in groups.iter().enumerate() {
+        let file = match images.get(&(look.tex_id, look.tint)) {
+            Some(f) => f.clone(),
+            None => {
+                let file = format!("{}_{}.png", stem, images.len());
+                fs::write(dir.join(&file), bake(scene, *look))?;
+                images.insert((look.tex_id, look.tint), file.clone());
+                file
+            }
+        };
+        let m = &scene.materials[look.mat_id];
+        let e = m.emissive;
+        mtl += &format!(
+            "newmtl material_{}\nKd {a} {a} {a}\nKs {s} {s} {s}\nNs {}\nNi {}\nd {}\nKe {} {} {}\nmap_Kd {}\n",
+            g, m.shininess, m.ior, 1.0 - m.transparency, e.r, e.g, e.b, file, a = m.albedo, s = m.specular,
+        );
+        if cutout(scene, *look) { mtl += &format!("map_d {}\n", file); }
+        mtl += "\n";
+
+        obj += &format!("usemtl material_{}\n", g);
+        for q in quads {
+            for (p, (u, v)) in q.p.iter().zip(q.uv) { obj += &format!("v {} {} {}\nvt {} {}\n", p[0], p[1], p[2], u, v); }
+            let n = q.face + 1;
+            obj += &format!("f {a}/{a}/{n} {b}/{b}/{n} {c}/{c}/{n} {d}/{d}/{n}\n", a = nv + 1, b = nv + 2, c = nv + 3, d = nv + 4);
+            nv += 4;
+        }
+    }
+    fs::write(dir.join(format!("{}.mtl", stem)), mtl)?;
+    fs::write(path, obj)
+}
+
+/// Añade `bytes` al búfer binario, alineado a 4, y devuelve el índice de su vista.
+fn push_view(bin: &mut Vec<u8>, views: &mut Vec<String>, bytes: &[u8], target: Option<u32>) -> usize {
+    while !bin.len().is_multiple_of(4) { bin.push(0); }
+    let target = target.map_or(String::new(), |t| format!(",\"target\":{}", t));
+    views.push(format!("{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{}{}}}", bin.len(), bytes.len(), target));
+    bin.extend_from_slice(bytes);
+    views.len() - 1
+}
+
+fn floats(v: &[f32]) -> Vec<u8> { v.iter().flat_map(|f| f.to_le_bytes()).collect() }
+
+fn list(items: &[String]) -> String { format!("[{}]", items.join(",")) }
+
+/// Una malla con una primitiva por aspecto y las imágenes PNG en el mismo búfer.
+/// El material es PBR sin metal, con la rugosidad equivalente al exponente de Phong.
+fn write_glb(path: &Path, scene: &Scene, groups: &[(Look, Vec<Quad>)]) -> io::Result<()> {
+    const ARRAY: Option<u32> = Some(34962);
+    const ELEMENTS: Option<u32> = Some(34963);
+    let (mut bin, mut views, mut accessors) = (Vec::new(), Vec::new(), Vec::new());
+    let (mut prims, mut materials, mut images, mut textures) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let mut image_of: HashMap<(usize, Option<[u32; 3]>), usize> = HashMap::new();
+
+    for (g, (look, quads)) in groups.iter().enumerate() {
+        let (mut pos, mut nor, mut uv, mut idx) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let (mut lo, mut hi) = ([f32::MAX; 3], [f32::MIN; 3]);
+        for (i, q) in quads.iter().enumerate() {
+            for (p, (u, v)) in q.p.iter().zip(q.uv) {
+                pos.extend(p);
+                nor.extend(NORMALS[q.face]);
+                // glTF cuenta v hacia abajo desde el borde superior
+                uv.extend([u, 1.0 - v]);
+                for k in 0..3 {
+                    lo[k] = lo[k].min(p[k]);
+                    hi[k] = hi[k].max(p[k]);
+                }
+            }
+            let base = i as u32 * 4;
+            idx.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        let n = quads.len() * 4;
+        let accessor = |view: usize, component: u32, count: usize, kind: &str, bounds: String| {
+            format!("{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"{}\"{}}}", view, component, count, kind, bounds)
+        };
+        let v = push_view(&mut bin, &mut views, &floats(&pos), ARRAY);
+        let bounds = format!(",\"min\":[{},{},{}],\"max\":[{},{},{}]", lo[0], lo[1], lo[2], hi[0], hi[1], hi[2]);
+        accessors.push(accessor(v, 5126, n, "VEC3", bounds));
+        let v = push_view(&mut bin, &mut views, &floats(&nor), ARRAY);
+        accessors.push(accessor(v, 5126, n, "VEC3", String::new()));
+        let v = push_view(&mut bin, &mut views, &floats(&uv), ARRAY);
+        accessors.push(accessor(v, 5126, n, "VEC2", String::new()));
+        let bytes: Vec<u8> = idx.iter().flat_map(|i| i.to_le_bytes()).collect();
+        let v = push_view(&mut bin, &mut views, &bytes, ELEMENTS);
+        accessors.push(accessor(v, 5125, idx.len(), "SCALAR", String::new()));
+        let a = accessors.len() - 4;
+        prims.push(format!(
+            "{{\"attributes\":{{\"POSITION\":{},\"NORMAL\":{},\"TEXCOORD_0\":{}}},\"indices\":{},\"material\":{}}}",
+            a, a + 1, a + 2, a + 3, g,
+        ));
+
+        let tex = match image_of.get(&(look.tex_id, look.tint)) {
+            Some(&t) => t,
+            None => {
+                let v = push_view(&mut bin, &mut views, &bake(scene, *look), None);
+                images.push(format!("{{\"bufferView\":{},\"mimeType\":\"image/png\"}}", v));
+                textures.push(format!("{{\"sampler\":0,\"source\":{}}}", images.len() - 1));
+                image_of.insert((look.tex_id, look.tint), textures.len() - 1);
+                textures.len() - 1
+            }
+        };
+        let m = &scene.materials[look.mat_id];
+        let e = m.emissive;
+        let mode = if cutout(scene, *look) {
+            ",\"alphaMode\":\"MASK\",\"alphaCutoff\":0.5,\"doubleSided\":true"
+        } else if m.transparency > 0.0 {
+            ",\"alphaMode\":\"BLEND\""
+        } else {
+            ""
+        };
+        materials.push(format!(
+            "{{\"name\":\"material_{}\",\"pbrMetallicRoughness\":{{\"baseColorFactor\":[{a},{a},{a},{}],\"baseColorTexture\":{{\"index\":{}}},\
+             \"metallicFactor\":0,\"roughnessFactor\":{}}},\"emissiveFactor\":[{},{},{}]{}}}",
+            g, 1.0 - m.transparency, tex, (2.0 / (m.shininess + 2.0)).powf(0.25),
+            e.r.clamp(0.0, 1.0), e.g.clamp(0.0, 1.0), e.b.clamp(0.0, 1.0), mode, a = m.albedo.clamp(0.0, 1.0),
+        ));
+    }
+    while !bin.len().is_multiple_of(4) { bin.push(0); }
+
+    // texeles nítidos y UV repetidas para los mosaicos
+    let sampler = "{\"magFilter\":9728,\"minFilter\":9728,\"wrapS\":10497,\"wrapT\":10497}";
+    let mut json = format!(
+        "{{\"asset\":{{\"version\":\"2.0\",\"generator\":\"minecraft_mine_rt\"}},\"scene\":0,\"scenes\":[{{\"nodes\":[0]}}],\
+         \"nodes\":[{{\"mesh\":0,\"name\":\"escena\"}}],\"meshes\":[{{\"primitives\":{}}}],\"materials\":{},\"textures\":{},\
+         \"images\":{},\"samplers\":[{}],\"accessors\":{},\"bufferViews\":{},\"buffers\":[{{\"byteLength\":{}}}]}}",
+        list(&prims), list(&materials), list(&textures), list(&images), sampler, list(&accessors), list(&views), bin.len(),
+    ).into_bytes();
+    while !json.len().is_multiple_of(4) { json.push(b' '); }
+
+    // cabecera, trozo JSON y trozo BIN
+    let mut out = Vec::with_capacity(28 + json.len() + bin.len());
+    out.extend(b"glTF");
+    out.extend(2u32.to_le_bytes());
+    out.extend(((28 + json.len() + bin.len()) as u32).to_le_bytes());
+    out.extend((json.len() as u32).to_le_bytes());
+    out.extend(b"JSON");
+    out.extend(json);
+    out.extend((bin.len() as u32).to_le_bytes());
+    out.extend(b"BIN\0");
+    out.extend(bin);
+    fs::write(path, out)
+}
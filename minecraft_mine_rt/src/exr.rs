@@ -0,0 +1,165 @@
+use std::fs;
+use std::io;
+
+use crate::framebuffer::Framebuffer;
+use crate::zlib::zlib_compress;
+
+// OpenEXR de una parte por líneas (scanline), con canales A, B, G y R en half o
+// float, sin comprimir o con ZIP (bloques de 16 líneas con zlib). El color va
+// premultiplicado, como pide el formato: solo la geometría, sin el cielo.
+
+#[derive(Clone, Copy)]
+pub enum Compression { None, Zip }
+
+#[derive(Clone, Copy)]
+pub enum Pixel { Half, Float }
+
+impl Compression {
+    pub fn by_name(name: &str) -> Option<Self> {
+        Some(match name { "none" => Compression::None, "zip" => Compression::Zip, _ => return None })
+    }
+}
+
+impl Pixel {
+    pub fn by_name(name: &str) -> Option<Self> {
+        Some(match name { "half" => Pixel::Half, "float" => Pixel::Float, _ => return None })
+    }
+}
+
+/// f32 a half (IEEE 754 de 16 bits), redondeando al par más cercano.
+fn half(x: f32) -> u16 {
+    let bits = x.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x7f_ffff;
+    if exp == 255 { return sign | 0x7c00 | if mant != 0 { 0x200 } else { 0 }; }
+    let e = exp - 127 + 15;
+    if e >= 31 { return sign | 0x7c00; }
+    // lo que se descarta decide el redondeo; un acarreo pasa bien al exponente
+    let round = |m: u32, shift: u32| {
+        let (kept, rest, halfway) = (m >> shift, m & ((1 << shift) - 1), 1 << (shift - 1));
+        kept + (rest > halfway || (rest == halfway && kept & 1 == 1)) as u32
+    };
+    if e <= 0 {
+        // subnormal: por debajo de 2^-25 queda en cero
+        if e < -10 { return sign; }
+        return sign | round(mant | 0x80_0000, (14 - e) as u32) as u16;
+    }
+    sign | (((e as u32) << 10) + round(mant, 13)) as u16
+}
+
+/// Prepara un bloque para zlib como la biblioteca de referencia: bytes pares y luego
+/// impares, y cada byte como diferencia con el anterior.
+fn predict(raw: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = raw.iter().step_by(2).chain(raw.iter().skip(1).step_by(2)).copied().collect();
+    for i in (1..out.len()).rev() {
+        out[i] = out[i].wrapping_sub(out[i - 1]).wrapping_add(128);
+    }
+    out
+}
+
+fn attr(out: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    for s in [name, kind] {
+        out.extend_from_slice(s.as_bytes());
+        out.push(0);
+    }
+    out.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    out.extend_from_slice(value);
+}
+
+pub fn write(path: &str, fb: &Framebuffer, compression: Compression, pixel: Pixel) -> io::Result<()> {
+    let ints = |v: &[i32]| v.iter().flat_map(|i| i.to_le_bytes()).collect::<Vec<u8>>();
+    let (w, h) = (fb.w as i32, fb.h as i32);
+    // los canales van en orden alfabético: índice en el píxel RGBA
+    let channels = [("A", 3), ("B", 2), ("G", 1), ("R", 0)];
+
+    let mut out = vec![0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0];
+    let mut list = Vec::new();
+    for (name, _) in channels {
+        list.extend_from_slice(name.as_bytes());
+        list.push(0);
+        list.extend(ints(&[match pixel { Pixel::Half => 1, Pixel::Float => 2 }]));
+        list.extend_from_slice(&[0, 0, 0, 0]); // pLinear y reservados
+        list.extend(ints(&[1, 1])); // muestreo en x e y
+    }
+    list.push(0);
+    attr(&mut out, "channels", "chlist", &list);
+    attr(&mut out, "compression", "compression", &[match compression { Compression::None => 0, Compression::Zip => 3 }]);
+    attr(&mut out, "dataWindow", "box2i", &ints(&[0, 0, w - 1, h - 1]));
+    attr(&mut out, "displayWindow", "box2i", &ints(&[0, 0, w - 1, h - 1]));
+    attr(&mut out, "lineOrder", "lineOrder", &[0]);
+    attr(&mut out, "pixelAspectRatio", "float", &1.0f32.to_le_bytes());
+    attr(&mut out, "screenWindowCenter", "v2f", &[0u8; 8]);
+    attr(&mut out, "screenWindowWidth", "float", &1.0f32.to_le_bytes());
+    out.push(0);
+
+    let lines = match compression { Compression::None => 1, Compression::Zip => 16 };
+    let chunks: Vec<Vec<u8>> = fb.px.chunks(fb.w * lines).map(|block| {
+        // cada línea lleva sus canales uno detrás de otro
+        let mut raw = Vec::new();
+        for row in block.chunks_exact(fb.w) {
+            for (_, c) in channels {
+                for p in row {
+                    match pixel {
+                        Pixel::Half => raw.extend_from_slice(&half(p[c]).to_le_bytes()),
+                        Pixel::Float => raw.extend_from_slice(&p[c].to_le_bytes()),
+                    }
+                }
+            }
+        }
+        match compression {
+            Compression::None => raw,
+            // si no gana nada se guarda tal cual; el lector lo nota por el tamaño
+            Compression::Zip => {
+                let packed = zlib_compress(&predict(&raw));
+                if packed.len() < raw.len() { packed } else { raw }
+            }
+        }
+    }).collect();
+
+    // tabla de posiciones de los bloques y después los bloques: línea inicial, tamaño, datos
+    let mut at = (out.len() + chunks.len() * 8) as u64;
+    for c in &chunks {
+        out.extend_from_slice(&at.to_le_bytes());
+        at += 8 + c.len() as u64;
+    }
+    for (i, c) in chunks.iter().enumerate() {
+        out.extend(ints(&[(i * lines) as i32, c.len() as i32]));
+        out.extend_from_slice(c);
+    }
+    fs::write(path, out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_values() {
+        assert_eq!(half(1.0), 0x3c00);
+        assert_eq!(half(-2.0), 0xc000);
+        assert_eq!(half(0.0), 0);
+        assert_eq!(half(65504.0), 0x7bff); // el mayor finito
+        assert_eq!(half(65520.0), 0x7c00); // a medio camino: redondea a infinito
+        assert_eq!(half(f32::INFINITY), 0x7c00);
+        assert_eq!(half(2f32.powi(-14)), 0x0400); // el menor normal
+        assert_eq!(half(2f32.powi(-24)), 0x0001); // el menor subnormal
+        assert_eq!(half(2f32.powi(-25)), 0); // empate: al par, que es cero
+        assert_eq!(half(1.5 * 2f32.powi(-25)), 0x0001);
+    }
+
+    #[test]
+    fn half_nan() {
+        let h = half(f32::NAN);
+        assert_eq!(h & 0x7c00, 0x7c00);
+        assert_ne!(h & 0x3ff, 0);
+    }
+
+    #[test]
+    fn predict_matches_reference() {
+        // pares [1, 3, 5], impares [2, 4] y luego diferencias + 128
+        assert_eq!(predict(&[1, 2, 3, 4, 5]), [1, 130, 130, 125, 130]);
+        assert_eq!(predict(&[0, 255]), [0, 127]);
+        assert!(predict(&[]).is_empty());
+    }
+}
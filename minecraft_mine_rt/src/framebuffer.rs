@@ -0,0 +1,46 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use crate::color::Color;
+use crate::tonemap::ToneMap;
+
+/// Imagen en coma flotante, lineal y sin recortar: lo que sale del trazado antes
+/// del mapeo de tonos, por filas desde arriba. `px` es la geometría en RGBA
+/// premultiplicado (A = cobertura, 0 donde solo se ve cielo) y `sky` lo que aporta
+/// el cielo en cada píxel; juntos dan la imagen completa.
+pub struct Framebuffer {
+    pub w: usize,
+    pub h: usize,
+    pub px: Vec<[f32; 4]>,
+    pub sky: Vec<[f32; 3]>,
+}
+
+impl Framebuffer {
+    pub fn new(w: usize, h: usize) -> Self { Self { w, h, px: vec![[0.0; 4]; w * h], sky: vec![[0.0; 3]; w * h] } }
+
+    /// Color completo del píxel `i`: geometría más cielo.
+    pub fn color(&self, i: usize) -> Color {
+        let ([r, g, b, _], [sr, sg, sb]) = (self.px[i], self.sky[i]);
+        Color::new(r + sr, g + sg, b + sb)
+    }
+
+    /// RGB8 con gamma, tras `tone` con `exposure` pasos (2^exposure).
+    pub fn to_rgb8(&self, tone: ToneMap, exposure: f32) -> Vec<u8> {
+        let scale = exposure.exp2();
+        (0..self.px.len()).flat_map(|i| tone.apply(self.color(i).mul(scale)).to_u8_gamma()).collect()
+    }
+}
+
+/// PFM en color (`PF`): RGB en float de 32 bits little-endian, filas desde abajo.
+pub fn write_pfm(path: &str, fb: &Framebuffer) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    // escala negativa = little-endian
+    write!(f, "PF\n{} {}\n-1.0\n", fb.w, fb.h)?;
+    for y in (0..fb.h).rev() {
+        for i in y * fb.w..(y + 1) * fb.w {
+            let c = fb.color(i);
+            for v in [c.r, c.g, c.b] { f.write_all(&v.to_le_bytes())?; }
+        }
+    }
+    f.flush()
+}
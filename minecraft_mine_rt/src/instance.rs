@@ -0,0 +1,56 @@
+use crate::aabb::{Aabb, Hit};
+use crate::bvh::Bvh;
+use crate::math::{Mat4, Ray, Vec3};
+
+// Geometría instanciada: cajas en su propio espacio, con su BVH, y una
+// transformación al mundo. El rayo se lleva al espacio del objeto, así las cajas
+// siguen alineadas a sus ejes aunque el objeto esté girado o escalado.
+
+fn min3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)) }
+fn max3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)) }
+
+pub struct Instance {
+    cubes: Vec<Aabb>,
+    bvh: Bvh,
+    to_world: Mat4,
+    to_object: Mat4,
+    /// Límites en el mundo: la caja del objeto transformada
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Instance {
+    pub fn new(cubes: Vec<Aabb>, to_world: Mat4) -> Self {
+        let bounds: Vec<(Vec3, Vec3)> = cubes.iter().map(|c| (c.min, c.max)).collect();
+        let first = bounds.first().copied().unwrap_or_default();
+        let (lo, hi) = bounds.iter().fold(first, |(a, b), c| (min3(a, c.0), max3(b, c.1)));
+        // las 8 esquinas de la caja del objeto, ya en el mundo
+        let corner = |i: i32| to_world.point(Vec3::new(
+            if i & 1 == 0 { lo.x } else { hi.x },
+            if i & 2 == 0 { lo.y } else { hi.y },
+            if i & 4 == 0 { lo.z } else { hi.z },
+        ));
+        let (min, max) = (1..8).fold((corner(0), corner(0)), |(a, b), i| (min3(a, corner(i)), max3(b, corner(i))));
+        Self { bvh: Bvh::build(&bounds), cubes, to_world, to_object: to_world.inverse(), min, max }
+    }
+
+    /// Impacto más cercano según `hit` (la intersección de la escena con una caja).
+    /// La dirección en el objeto no se normaliza, así `t` vale igual en el mundo.
+    pub fn hit(&self, ray: &Ray, hit: impl Fn(&Ray, &Aabb) -> Option<Hit>) -> Option<Hit> {
+        let local = Ray { o: self.to_object.point(ray.o), d: self.to_object.dir(ray.d), time: ray.time };
+        let mut best: Option<Hit> = None;
+        self.bvh.visit(&local, |i| {
+            let h = hit(&local, &self.cubes[i])?;
+            if best.as_ref().is_some_and(|b| b.t <= h.t) { return None; }
+            let t = h.t;
+            best = Some(h);
+            Some(t)
+        });
+        best.map(|mut h| {
+            h.p = self.to_world.point(h.p);
+            // las normales van con la inversa traspuesta
+            h.n = self.to_object.transpose().dir(h.n).norm();
+            h
+        })
+    }
+}
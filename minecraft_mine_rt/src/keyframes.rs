@@ -0,0 +1,122 @@
+use crate::camera::{Camera, Lens, Projection};
+use crate::math::{Mat4, Quat, Vec3};
+
+// Recorridos de cámara: claves con ojo, objetivo, campo de visión y alabeo en
+// segundos, unidas por rectas, curvas de Catmull-Rom o de Bézier, con una curva de
+// aceleración en cada tramo.
+
+#[derive(Clone, Copy)]
+pub enum Interp {
+    Linear,
+    /// Pasa por todas las claves con velocidad continua
+    CatmullRom,
+    /// Puntos de control explícitos (`*_handle`); sin ellos la cámara se detiene en cada clave
+    Bezier,
+}
+
+impl Interp {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "linear" => Interp::Linear,
+            "catmull-rom" => Interp::CatmullRom,
+            "bezier" => Interp::Bezier,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Ease { Linear, In, Out, InOut }
+
+impl Ease {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "linear" => Ease::Linear,
+            "in" => Ease::In,
+            "out" => Ease::Out,
+            "in-out" => Ease::InOut,
+            _ => return None,
+        })
+    }
+
+    fn apply(self, u: f32) -> f32 {
+        match self {
+            Ease::Linear => u,
+            Ease::In => u * u,
+            Ease::Out => u * (2.0 - u),
+            Ease::InOut => u * u * (3.0 - 2.0 * u),
+        }
+    }
+}
+
+pub struct Key {
+    pub time: f32,
+    pub eye: Vec3,
+    pub target: Vec3,
+    pub fov: f32,
+    /// Giro alrededor de la dirección de vista en grados; positivo, alabeo a la derecha
+    pub roll: f32,
+    /// Aceleración del tramo que empieza en esta clave
+    pub ease: Ease,
+    /// Bézier: punto de control de salida relativo al ojo (el de entrada es el opuesto)
+    pub eye_handle: Option<Vec3>,
+    pub target_handle: Option<Vec3>,
+}
+
+pub struct CameraPath {
+    keys: Vec<Key>,
+    interp: Interp,
+    up: Vec3,
+    /// Lente de todas las cámaras del recorrido; sin `focus` enfoca el objetivo de cada instante
+    pub lens: Lens,
+    pub projection: Projection,
+}
+
+impl CameraPath {
+    /// `keys` ordenadas por tiempo, sin repetir tiempos y al menos una.
+    pub fn new(keys: Vec<Key>, interp: Interp, up: Vec3) -> Self { Self { keys, interp, up, lens: Lens::default(), projection: Projection::default() } }
+
+    /// Cámara en `time` segundos; antes de la primera clave y después de la última, quieta.
+    pub fn camera(&self, time: f32, aspect: f32) -> Camera {
+        let k = &self.keys;
+        let (eye, target, view) = if k.len() == 1 {
+            (k[0].eye, k[0].target, Vec3::new(k[0].fov, k[0].roll, 0.0))
+        } else {
+            let i = k.iter().rposition(|key| key.time <= time).unwrap_or(0).min(k.len() - 2);
+            let u = ((time - k[i].time) / (k[i + 1].time - k[i].time)).clamp(0.0, 1.0);
+            let u = k[i].ease.apply(u);
+            (
+                self.curve(i, u, |k| k.eye, |k| k.eye_handle),
+                self.curve(i, u, |k| k.target, |k| k.target_handle),
+                self.curve(i, u, |k| Vec3::new(k.fov, k.roll, 0.0), |_| None),
+            )
+        };
+        let fwd = target.sub(eye).norm();
+        let up = Mat4::trs(Vec3::default(), Quat::from_axis_angle(fwd, view.y.to_radians()), 1.0).dir(self.up);
+        let mut cam = Camera::new(eye, target, up, view.x, aspect);
+        cam.lens = self.lens;
+        cam.projection = self.projection;
+        cam
+    }
+
+    /// Punto del tramo `i` en `u` como Bézier cúbica. En Catmull-Rom los puntos de
+    /// control salen de la tangente en cada clave, por diferencias con sus vecinas.
+    fn curve(&self, i: usize, u: f32, value: impl Fn(&Key) -> Vec3, handle: impl Fn(&Key) -> Option<Vec3>) -> Vec3 {
+        let k = &self.keys;
+        let (p0, p3) = (value(&k[i]), value(&k[i + 1]));
+        let dt = k[i + 1].time - k[i].time;
+        // unidades por segundo; en los extremos, hacia el único vecino
+        let tangent = |j: usize| {
+            let (lo, hi) = (j.saturating_sub(1), (j + 1).min(k.len() - 1));
+            value(&k[hi]).sub(value(&k[lo])).mul(1.0 / (k[hi].time - k[lo].time))
+        };
+        let (h0, h1) = match self.interp {
+            Interp::Linear => return p0.add(p3.sub(p0).mul(u)),
+            Interp::CatmullRom => (tangent(i).mul(dt / 3.0), tangent(i + 1).mul(dt / 3.0)),
+            Interp::Bezier => (handle(&k[i]).unwrap_or_default(), handle(&k[i + 1]).unwrap_or_default()),
+        };
+        let (p1, p2) = (p0.add(h0), p3.sub(h1));
+        let v = 1.0 - u;
+        p0.mul(v * v * v).add(p1.mul(3.0 * v * v * u)).add(p2.mul(3.0 * v * u * u)).add(p3.mul(u * u * u))
+    }
+}
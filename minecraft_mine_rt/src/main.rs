@@ -1,58 +1,288 @@
-mod math;      use math::{Vec3, Ray};
+mod math;      use math::Ray;
 mod color;     use color::Color;
 mod ppm;       use ppm::write_ppm;
 mod aabb;
+mod biome;
+mod block;
+mod cli;       use cli::{Format, Options};
 mod material;
 mod texture;
-mod skybox;
-mod camera;    use camera::Camera;
-mod renderer;  use renderer::trace;
-mod scene;     use scene::{build_scene_minecraft, build_scene_basic};
+mod skybox;    use skybox::{CubeMap, FACE_NAMES};
+mod camera;    use camera::{Camera, Layout, Stereo};
+mod renderer;  use renderer::{trace, Scene};
+mod scene;     use scene::{build_scene_minecraft, build_scene_terrain};
+mod scenefile;
+mod vox;
+mod zlib;
+mod nbt;
+mod schematic;
+mod anvil;
+mod rng;       use rng::Rng;
+mod noise;
+mod bvh;
+mod world;
+mod terrain;
+mod vegetation;
+mod structures;
+mod merge;
+mod instance;
+mod shape;
+mod obj;
+mod png;
+mod export;
+mod keyframes;
+mod framebuffer; use framebuffer::{write_pfm, Framebuffer};
+mod tonemap;
+mod exr;
 
 use std::fs;
 
 fn main() {
+    let opts = Options::from_args().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+
     // Config render
-    let width: usize = 640;
-    let height: usize = 360;
+    let width = opts.width;
+    let height = opts.height;
     // 180 frames ≈ 6s a 30fps. La cámara da UNA vuelta completa en este lapso.
-    let frames: usize = 180;
+    let frames = opts.frames;
+    let fps = 30.0;
+    let aspect = width as f32 / height as f32;
     fs::create_dir_all("out").ok();
 
+    // las escenas animadas se rehacen en cada cuadro; el recuento se muestra una vez
+    let mut reported = false;
+
+    // Escena desde archivo: se carga una vez y solo avanza el tiempo (y las ediciones con `time`)
+    let mut from_file = opts.scene.as_ref().map(|path| {
+        scenefile::load(path, aspect).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+
+    let mut path = opts.camera.as_ref().map(|file| {
+        scenefile::load_path(file, aspect).unwrap_or_else(|e| {
+            eprintln!("{}: {}", file, e);
+            std::process::exit(1);
+        })
+    });
+    // con --scene, el recorrido de --camera reemplaza al del archivo
+    if let Some(file) = from_file.as_mut() && path.is_some() { file.path = path.take(); }
+    // la lente y la proyección de la línea de órdenes mandan sobre las de los archivos
+    if let Some(file) = from_file.as_mut() {
+        if let Some(lens) = opts.lens { file.camera.lens = lens; }
+        if let Some(projection) = opts.projection { file.camera.projection = projection; }
+    }
+    for p in from_file.as_mut().and_then(|f| f.path.as_mut()).into_iter().chain(path.as_mut()) {
+        if let Some(lens) = opts.lens { p.lens = lens; }
+        if let Some(projection) = opts.projection { p.projection = projection; }
+    }
+
+    // obturador en segundos respecto del cuadro; cerrado = sin desenfoque de movimiento
+    let shutter = opts.shutter.map_or((0.0, 0.0), |(a, b)| (a / fps, b / fps));
+    if let Some(file) = from_file.as_mut() { file.scene.shutter = shutter; }
+
     for f in 0..frames {
+        if opts.only_frame.is_some_and(|k| k != f) { continue; }
         let t = f as f32 / (frames as f32 - 1.0); // 0..1
 
-        // Elige escena
-        // let (scene, anim) = build_scene_basic(t);
-        let (scene, anim) = build_scene_minecraft(t);
-
-        // Cámara en órbita alrededor del centro de la escena
-        let target = Vec3::new(8.0, 2.0, 8.0);
-        let eye = Vec3::new(
-            target.x + anim.radius * anim.angle.cos(),
-            anim.eye_h,
-            target.z + anim.radius * anim.angle.sin(),
-        );
-        let cam = Camera::new(eye, target, Vec3::new(0.0, 1.0, 0.0), 60.0, width as f32 / height as f32);
-
-        // Framebuffer
-        let mut rgb = vec![0u8; width * height * 3];
-
-        for y in 0..height {
-            for x in 0..width {
-                let ray: Ray = cam.ray_for(x, y, width, height);
-                let col: Color = trace(&scene, ray);
-                let p = (y * width + x) * 3;
-                let [r, g, b] = col.to_u8_gamma();
-                rgb[p] = r; rgb[p + 1] = g; rgb[p + 2] = b;
+        let time = f as f32 / fps; // tiempo real del cuadro para texturas animadas (agua, lava)
+        let built;
+        let (scene, poses) = match from_file.as_mut() {
+            Some(file) => {
+                if let Some(stats) = file.update(time, opts.section)
+                    && !reported
+                {
+                    report(&stats);
+                    reported = true;
+                }
+                let pose = |s: f32| file.path.as_ref().map_or(file.camera, |p| p.camera(time + s, aspect));
+                (&file.scene, [pose(shutter.0), pose(shutter.1)])
+            }
+            None => {
+                let (mut scene, anim) = match &opts.terrain {
+                    Some(params) => build_scene_terrain(t, opts.biome, params),
+                    None => build_scene_minecraft(t, opts.biome),
+                };
+                (scene.time, scene.shutter) = (time, shutter);
+
+                // Cámara en órbita alrededor del centro de la escena, o el recorrido de --camera,
+                // `s` segundos después del cuadro
+                let pose = |s: f32| match &path {
+                    Some(path) => path.camera(time + s, aspect),
+                    None => {
+                        let mut cam = anim.camera(t + s * fps / (frames as f32 - 1.0), aspect);
+                        cam.lens = opts.lens.unwrap_or_default();
+                        cam.projection = opts.projection.unwrap_or_default();
+                        cam
+                    }
+                };
+                let poses = [pose(shutter.0), pose(shutter.1)];
+                if let Some(cut) = opts.section { cut.apply(&mut scene.cubes, poses[0].view_eye()); }
+                let stats = scene.optimize();
+                if !reported { report(&stats); reported = true; }
+                scene.build_bvh();
+                built = scene;
+                (&built, poses)
+            }
+        };
+        let shot = Shot { poses, times: (time + shutter.0, time + shutter.1) };
+
+        if let Some(path) = &opts.export {
+            match export::write(path, scene) {
+                Ok(s) => println!("Exportadas {} caras con {} materiales: {}", s.faces, s.materials, path),
+                Err(e) => {
+                    eprintln!("{}: {}", path, e);
+                    std::process::exit(1);
+                }
             }
+            if !scene.instances.is_empty() || !scene.shapes.is_empty() {
+                eprintln!("aviso: las instancias y las formas (esferas, mallas…) no se exportan");
+            }
+            return;
+        }
+        let samples = opts.samples.unwrap_or(if poses[0].lens.aperture > 0.0 || opts.shutter.is_some() { 16 } else { 1 });
+        if let Some(dir) = &opts.cubemap {
+            // seis caras cuadradas de lado `height`, alineadas con los ejes del mundo
+            fs::create_dir_all(dir).expect("no pude crear la carpeta del cubemap");
+            for (face, name) in FACE_NAMES.iter().enumerate() {
+                let fb = render_frame(scene, height, height, samples, |px, py, _, u| {
+                    let (cam, time) = shot.at(u);
+                    Ray { o: cam.view_eye(), d: CubeMap::face_dir(face, px / height as f32, py / height as f32).norm(), time }
+                });
+                let rgb = fb.to_rgb8(opts.tone, opts.exposure);
+                let path = format!("{}/{}.ppm", dir, name);
+                write_ppm(&path, height, height, &rgb).expect("no pude escribir el PPM");
+            }
+            println!("Cubemap listo: {}/ (posx, negx, posy, negy, posz, negz)", dir);
+            return;
         }
+        let fb = match &opts.stereo {
+            Some(stereo) => render_stereo(scene, &shot, stereo, width, height, samples),
+            None => render_frame(scene, width, height, samples, |px, py, lens, u| shot.ray(px, py, width, height, lens, u)),
+        };
 
-        let path = format!("out/frame_{:04}.ppm", f);
-        write_ppm(&path, width, height, &rgb).expect("no pude escribir el PPM");
+        // el mapeo de tonos solo toca la salida de 8 bits
+        let (path, written) = match opts.format {
+            Format::Ppm => {
+                let path = format!("out/frame_{:04}.ppm", f);
+                let written = write_ppm(&path, width, height, &fb.to_rgb8(opts.tone, opts.exposure));
+                (path, written)
+            }
+            Format::Pfm => {
+                let path = format!("out/frame_{:04}.pfm", f);
+                let written = write_pfm(&path, &fb);
+                (path, written)
+            }
+            Format::Exr(compression, pixel) => {
+                let path = format!("out/frame_{:04}.exr", f);
+                let written = exr::write(&path, &fb, compression, pixel);
+                (path, written)
+            }
+        };
+        if let Err(e) = written {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        }
         println!("Frame {} listo: {}", f, path);
     }
 
-    println!("Listo. Combina los frames con ffmpeg:");
-    println!(r#"  ffmpeg -framerate 30 -i out/frame_%04d.ppm -pix_fmt yuv420p -crf 18 diorama.mp4"#);
+    if let Format::Ppm = opts.format {
+        println!("Listo. Combina los frames con ffmpeg:");
+        println!(r#"  ffmpeg -framerate 30 -i out/frame_%04d.ppm -pix_fmt yuv420p -crf 18 diorama.mp4"#);
+    } else {
+        println!("Listo.");
+    }
+}
+
+fn report(s: &merge::Stats) {
+    println!("Geometría: {} cajas -> {} ({} bloques ocultos, el resto fusionado)", s.before, s.after, s.hidden);
+}
+
+/// Poses de la cámara al abrir y al cerrar el obturador, y en qué segundos.
+struct Shot {
+    poses: [Camera; 2],
+    times: (f32, f32),
+}
+
+impl Shot {
+    /// Cámara y tiempo en `u` (0 = abre, 1 = cierra), interpolando entre las poses.
+    fn at(&self, u: f32) -> (Camera, f32) {
+        (self.poses[0].lerp(&self.poses[1], u), self.times.0 + (self.times.1 - self.times.0) * u)
+    }
+
+    fn ray(&self, px: f32, py: f32, w: usize, h: usize, lens: (f32, f32), u: f32) -> Ray {
+        let (cam, time) = self.at(u);
+        Ray { time, ..cam.ray_for(px, py, w, h, lens) }
+    }
+}
+
+/// Con una muestra, un rayo por el centro del píxel; con más, cada una en un punto
+/// al azar del píxel y de la lente, y el color es la media (antes de la gamma).
+/// `ray` recibe el punto de la imagen en píxeles, el de la lente en [0, 1)² y el
+/// instante del obturador en [0, 1).
+fn render_frame(scene: &Scene, width: usize, height: usize, samples: usize, ray: impl Fn(f32, f32, (f32, f32), f32) -> Ray) -> Framebuffer {
+    let mut fb = Framebuffer::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            // semilla por píxel: el ruido no cambia entre cuadros ni entre ejecuciones
+            let mut rng = Rng::new((y * width + x) as u64);
+            // lo que choca con la geometría y lo que ve el cielo, por separado
+            let (mut col, mut sky, mut alpha) = (Color::black(), Color::black(), 0.0);
+            for _ in 0..samples {
+                let (jx, jy) = if samples == 1 { (0.5, 0.5) } else { (rng.f32(), rng.f32()) };
+                let lens = (rng.f32(), rng.f32());
+                match trace(scene, ray(x as f32 + jx, y as f32 + jy, lens, rng.f32())) {
+                    (c, a) if a > 0.0 => (col, alpha) = (col.add(c), alpha + a),
+                    (c, _) => sky = sky.add(c),
+                }
+            }
+            let k = 1.0 / samples as f32;
+            let (col, sky) = (col.mul(k), sky.mul(k));
+            fb.px[y * width + x] = [col.r, col.g, col.b, alpha * k];
+            fb.sky[y * width + x] = [sky.r, sky.g, sky.b];
+        }
+    }
+    fb
+}
+
+/// Las dos vistas de `stereo` juntas en un cuadro de `width`×`height`.
+fn render_stereo(scene: &Scene, shot: &Shot, stereo: &Stereo, width: usize, height: usize, samples: usize) -> Framebuffer {
+    let (w, h) = match stereo.layout {
+        Layout::SideBySide => (width / 2, height),
+        Layout::OverUnder => (width, height / 2),
+        Layout::Anaglyph => (width, height),
+    };
+    let aspect = w as f32 / h as f32;
+    let [open, close] = shot.poses.map(|c| stereo.eyes(&c, aspect));
+    let [left, right] = [0, 1].map(|k| {
+        let eye = Shot { poses: [open[k], close[k]], times: shot.times };
+        render_frame(scene, w, h, samples, |px, py, lens, u| eye.ray(px, py, w, h, lens, u))
+    });
+    let mut fb = Framebuffer::new(width, height);
+    match stereo.layout {
+        Layout::Anaglyph => {
+            for (p, (l, r)) in fb.px.iter_mut().zip(left.px.iter().zip(&right.px)) {
+                *p = [l[0], r[1], r[2], l[3].max(r[3])];
+            }
+            for (s, (l, r)) in fb.sky.iter_mut().zip(left.sky.iter().zip(&right.sky)) {
+                *s = [l[0], r[1], r[2]];
+            }
+        }
+        // cada vista en su rectángulo; si el tamaño es impar sobra una línea negra
+        Layout::SideBySide | Layout::OverUnder => {
+            let (dx, dy) = if let Layout::SideBySide = stereo.layout { (w, 0) } else { (0, h) };
+            for (k, view) in [left, right].iter().enumerate() {
+                for y in 0..h {
+                    let at = (y + k * dy) * width + k * dx;
+                    fb.px[at..at + w].copy_from_slice(&view.px[y * w..(y + 1) * w]);
+                    fb.sky[at..at + w].copy_from_slice(&view.sky[y * w..(y + 1) * w]);
+                }
+            }
+        }
+    }
+    fb
 }
@@ -6,7 +6,6 @@ impl Vec3 {
     pub fn add(self,o:Self)->Self{Self::new(self.x+o.x,self.y+o.y,self.z+o.z)}
     pub fn sub(self,o:Self)->Self{Self::new(self.x-o.x,self.y-o.y,self.z-o.z)}
     pub fn mul(self,s:f32)->Self{Self::new(self.x*s,self.y*s,self.z*s)}
-    pub fn hadamard(self,o:Self)->Self{Self::new(self.x*o.x,self.y*o.y,self.z*o.z)}
     pub fn dot(self,o:Self)->f32{self.x*o.x + self.y*o.y + self.z*o.z}
     pub fn cross(self,o:Self)->Self{
         Self::new(self.y*o.z-self.z*o.y, self.z*o.x-self.x*o.z, self.x*o.y-self.y*o.x)
@@ -16,7 +15,7 @@ impl Vec3 {
 }
 
 #[derive(Clone, Copy, Debug)]
-pub struct Ray { pub o: Vec3, pub d: Vec3 } // d debe venir normalizado
+pub struct Ray { pub o: Vec3, pub d: Vec3, pub time: f32 } // d debe venir normalizado; time en segundos de la escena
 
 pub fn reflect(i:Vec3, n:Vec3)->Vec3 { i.sub(n.mul(2.0*i.dot(n))) }
 
@@ -32,3 +31,83 @@ pub fn schlick(cos:f32, n1:f32, n2:f32)->f32{
     let r0 = ((n1-n2)/(n1+n2)).powi(2);
     r0 + (1.0 - r0) * (1.0 - cos).powi(5)
 }
+
+/// Rotación como cuaternión unitario (w + xi + yj + zk).
+#[derive(Clone, Copy, Debug)]
+pub struct Quat { pub w: f32, pub x: f32, pub y: f32, pub z: f32 }
+
+impl Quat {
+    /// Giro de `angle` radianes alrededor de `axis` (regla de la mano derecha).
+    pub fn from_axis_angle(axis:Vec3, angle:f32)->Self{
+        let a = axis.norm().mul((angle*0.5).sin());
+        Self{ w:(angle*0.5).cos(), x:a.x, y:a.y, z:a.z }
+    }
+    /// Giros en radianes alrededor de x, luego y, luego z.
+    pub fn from_euler(r:Vec3)->Self{
+        let q = |x,y,z,a| Self::from_axis_angle(Vec3::new(x,y,z), a);
+        q(0.0,0.0,1.0,r.z).mul(q(0.0,1.0,0.0,r.y).mul(q(1.0,0.0,0.0,r.x)))
+    }
+    /// Composición: primero `o` y luego `self`.
+    pub fn mul(self,o:Self)->Self{
+        Self{
+            w: self.w*o.w - self.x*o.x - self.y*o.y - self.z*o.z,
+            x: self.w*o.x + self.x*o.w + self.y*o.z - self.z*o.y,
+            y: self.w*o.y - self.x*o.z + self.y*o.w + self.z*o.x,
+            z: self.w*o.z + self.x*o.y - self.y*o.x + self.z*o.w,
+        }
+    }
+}
+
+/// Transformación afín 4x4 por filas; la última fila es siempre (0, 0, 0, 1).
+#[derive(Clone, Copy, Debug)]
+pub struct Mat4 { pub m: [[f32;4];4] }
+
+impl Mat4 {
+    /// Escala uniforme, luego rotación y por último traslación.
+    pub fn trs(t:Vec3, r:Quat, s:f32)->Self{
+        let Quat{w,x,y,z} = r;
+        let m = [
+            [1.0-2.0*(y*y+z*z), 2.0*(x*y-w*z),     2.0*(x*z+w*y),     t.x],
+            [2.0*(x*y+w*z),     1.0-2.0*(x*x+z*z), 2.0*(y*z-w*x),     t.y],
+            [2.0*(x*z-w*y),     2.0*(y*z+w*x),     1.0-2.0*(x*x+y*y), t.z],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        Self{ m: m.map(|row| [row[0]*s, row[1]*s, row[2]*s, row[3]]) }
+    }
+    /// Primero `o` y luego `self`.
+    pub fn mul(self,o:Self)->Self{
+        let mut m = [[0.0;4];4];
+        for (i,row) in m.iter_mut().enumerate() {
+            for (j,v) in row.iter_mut().enumerate() { *v = (0..4).map(|k| self.m[i][k]*o.m[k][j]).sum(); }
+        }
+        Self{m}
+    }
+    pub fn point(&self,p:Vec3)->Vec3{ self.dir(p).add(Vec3::new(self.m[0][3],self.m[1][3],self.m[2][3])) }
+    /// Vector sin traslación (direcciones de rayos).
+    pub fn dir(&self,v:Vec3)->Vec3{
+        let r = |i:usize| self.m[i][0]*v.x + self.m[i][1]*v.y + self.m[i][2]*v.z;
+        Vec3::new(r(0),r(1),r(2))
+    }
+    /// Traspuesta de la parte lineal: aplicada a la inversa lleva normales al otro espacio.
+    pub fn transpose(&self)->Self{
+        let mut m = [[0.0,0.0,0.0,0.0],[0.0,0.0,0.0,0.0],[0.0,0.0,0.0,0.0],[0.0,0.0,0.0,1.0]];
+        for (i,row) in m.iter_mut().take(3).enumerate() { for (j,v) in row.iter_mut().take(3).enumerate() { *v = self.m[j][i]; } }
+        Self{m}
+    }
+    /// Inversa de una transformación afín (parte lineal invertible).
+    pub fn inverse(&self)->Self{
+        let a = &self.m;
+        let c = |i:usize,j:usize| {
+            let (r0,r1) = ((i+1)%3,(i+2)%3);
+            let (c0,c1) = ((j+1)%3,(j+2)%3);
+            a[r0][c0]*a[r1][c1] - a[r0][c1]*a[r1][c0]
+        };
+        let det = a[0][0]*c(0,0) + a[0][1]*c(0,1) + a[0][2]*c(0,2);
+        let mut m = [[0.0,0.0,0.0,0.0],[0.0,0.0,0.0,0.0],[0.0,0.0,0.0,0.0],[0.0,0.0,0.0,1.0]];
+        for (i,row) in m.iter_mut().take(3).enumerate() { for (j,v) in row.iter_mut().take(3).enumerate() { *v = c(j,i)/det; } }
+        let mut inv = Self{m};
+        let t = inv.dir(Vec3::new(a[0][3],a[1][3],a[2][3])).mul(-1.0);
+        (inv.m[0][3],inv.m[1][3],inv.m[2][3]) = (t.x,t.y,t.z);
+        inv
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::aabb::Aabb;
+use crate::material::Material;
+use crate::math::Vec3;
+use crate::texture::Texture;
+
+// Preproceso de la lista de cubos: quita los bloques encerrados por bloques opacos
+// y une los vecinos iguales en cajas más grandes (con UV en mosaico por bloque).
+
+/// Cajas antes del preproceso, bloques descartados por ocultos y cajas resultantes.
+pub struct Stats {
+    pub before: usize,
+    pub hidden: usize,
+    pub after: usize,
+}
+
+/// Solo se unen bloques que se ven igual: material, texturas por cara y tinte.
+type Key = (usize, Option<[usize; 6]>, Option<([u32; 3], u8)>);
+
+fn key(c: &Aabb) -> Key {
+    let tint = c.tint.map(|t| ([t.color.r.to_bits(), t.color.g.to_bits(), t.color.b.to_bits()], t.faces));
+    (c.mat_id, c.face_tex, tint)
+}
+
+/// Esquina de un bloque unitario alineado con la rejilla; el resto de cajas no se toca.
+fn cell(c: &Aabb) -> Option<[i32; 3]> {
+    let s = c.max.sub(c.min);
+    let unit = |d: f32| (d - 1.0).abs() < 1e-4;
+    let int = |v: f32| (v - v.round()).abs() < 1e-4;
+    (unit(s.x) && unit(s.y) && unit(s.z) && int(c.min.x) && int(c.min.y) && int(c.min.z))
+        .then(|| [c.min.x.round() as i32, c.min.y.round() as i32, c.min.z.round() as i32])
+}
+
+/// Tapa la vista si no deja pasar luz ni tiene texels recortados en ninguna cara.
+pub fn opaque(c: &Aabb, materials: &[Material], textures: &[Texture]) -> bool {
+    let m = &materials[c.mat_id];
+    m.transparency <= 0.0 && (0..6).all(|f| !matches!(textures[c.face_tex.map_or(m.tex_id, |t| t[f])], Texture::Plant { .. }))
+}
+
+pub fn optimize(cubes: Vec<Aabb>, materials: &[Material], textures: &[Texture]) -> (Vec<Aabb>, Stats) {
+    let before = cubes.len();
+    let opaque = |c: &Aabb| opaque(c, materials, textures);
+
+    // un bloque por celda; si dos cajas coinciden, la segunda sigue tal cual
+    let mut grid: HashMap<[i32; 3], (usize, Key, bool)> = HashMap::new();
+    let mut out = Vec::new();
+    let mut loose = Vec::new();
+    for (i, c) in cubes.iter().enumerate() {
+        match cell(c) {
+            Some(p) if !grid.contains_key(&p) => { grid.insert(p, (i, key(c), opaque(c))); }
+            _ => loose.push(i),
+        }
+    }
+
+    const NEIGHBORS: [[i32; 3]; 6] = [[-1, 0, 0], [1, 0, 0], [0, -1, 0], [0, 1, 0], [0, 0, -1], [0, 0, 1]];
+    let hidden: HashSet<[i32; 3]> = grid.keys().copied().filter(|p| {
+        NEIGHBORS.iter().all(|d| grid.get(&[p[0] + d[0], p[1] + d[1], p[2] + d[2]]).is_some_and(|n| n.2))
+    }).collect();
+
+    // fusión voraz: se alarga en x, luego la fila en z y luego el rectángulo en y
+    let mut cells: Vec<[i32; 3]> = grid.keys().copied().filter(|p| !hidden.contains(p)).collect();
+    cells.sort_by_key(|p| (p[1], p[2], p[0]));
+    let mut used = HashSet::new();
+    for p in cells {
+        if used.contains(&p) { continue; }
+        let (i, k, _) = grid[&p];
+        let same = |q: [i32; 3], used: &HashSet<[i32; 3]>| {
+            !used.contains(&q) && !hidden.contains(&q) && grid.get(&q).is_some_and(|n| n.1 == k)
+        };
+        let mut n = [1, 1, 1];
+        while same([p[0] + n[0], p[1], p[2]], &used) { n[0] += 1; }
+        while (0..n[0]).all(|x| same([p[0] + x, p[1], p[2] + n[2]], &used)) { n[2] += 1; }
+        while (0..n[2]).all(|z| (0..n[0]).all(|x| same([p[0] + x, p[1] + n[1], p[2] + z], &used))) { n[1] += 1; }
+        for y in 0..n[1] {
+            for z in 0..n[2] {
+                for x in 0..n[0] { used.insert([p[0] + x, p[1] + y, p[2] + z]); }
+            }
+        }
+        let mut c = cubes[i].clone();
+        if n != [1, 1, 1] {
+            c.max = c.min.add(Vec3::new(n[0] as f32, n[1] as f32, n[2] as f32));
+            c.tiled = true;
+        }
+        out.push(c);
+    }
+    out.extend(loose.into_iter().map(|i| cubes[i].clone()));
+    let stats = Stats { before, hidden: hidden.len(), after: out.len() };
+    (out, stats)
+}
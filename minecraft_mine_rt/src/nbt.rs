@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use crate::zlib;
+
+/// Tag NBT (formato binario de Minecraft, big-endian).
+#[derive(Clone, Debug)]
+pub enum Tag {
+    End,
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(HashMap<String, Tag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        if let Tag::Compound(m) = self { m.get(key) } else { None }
+    }
+
+    /// Cualquier tag entero como i64.
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Tag::Byte(v) => Some(v as i64),
+            Tag::Short(v) => Some(v as i64),
+            Tag::Int(v) => Some(v as i64),
+            Tag::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Cualquier tag numérico como f64.
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Tag::Float(v) => Some(v as f64),
+            Tag::Double(v) => Some(v),
+            _ => self.as_int().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        if let Tag::String(s) = self { Some(s) } else { None }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        if let Tag::List(l) = self { Some(l) } else { None }
+    }
+
+    pub fn as_compound(&self) -> Option<&HashMap<String, Tag>> {
+        if let Tag::Compound(m) = self { Some(m) } else { None }
+    }
+
+    /// Listas numéricas y arreglos Int/Byte/Long como Vec<i64> (los reales se truncan).
+    pub fn as_ints(&self) -> Option<Vec<i64>> {
+        match self {
+            Tag::ByteArray(a) => Some(a.iter().map(|&v| v as i64).collect()),
+            Tag::IntArray(a) => Some(a.iter().map(|&v| v as i64).collect()),
+            Tag::LongArray(a) => Some(a.clone()),
+            Tag::List(l) => l.iter().map(|t| t.as_f64().map(|v| v.floor() as i64)).collect(),
+            _ => None,
+        }
+    }
+}
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+/// Lee un archivo NBT (gzip, zlib o sin comprimir); devuelve el nombre y el tag raíz.
+pub fn load(path: &str) -> io::Result<(String, Tag)> {
+    read(&fs::read(path)?)
+}
+
+pub fn read(data: &[u8]) -> io::Result<(String, Tag)> {
+    let raw = zlib::decompress_auto(data)?;
+    let mut r = Reader { data: &raw, pos: 0 };
+    let kind = r.u8()?;
+    if kind != 10 { return Err(bad("la raíz NBT debe ser un compound")); }
+    let name = r.string()?;
+    let tag = r.payload(kind, 0)?;
+    Ok((name, tag))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let b = self.data.get(self.pos..self.pos + N).ok_or_else(|| bad("NBT truncado"))?;
+        self.pos += N;
+        Ok(b.try_into().unwrap())
+    }
+    fn u8(&mut self) -> io::Result<u8> { Ok(self.take::<1>()?[0]) }
+    fn i16(&mut self) -> io::Result<i16> { Ok(i16::from_be_bytes(self.take()?)) }
+    fn i32(&mut self) -> io::Result<i32> { Ok(i32::from_be_bytes(self.take()?)) }
+    fn i64(&mut self) -> io::Result<i64> { Ok(i64::from_be_bytes(self.take()?)) }
+
+    fn len(&mut self) -> io::Result<usize> {
+        let n = self.i32()?;
+        // cota para no reservar memoria absurda con datos corruptos
+        if n < 0 || n as usize > self.data.len() - self.pos + 1 { return Err(bad("longitud NBT inválida")); }
+        Ok(n as usize)
+    }
+
+    /// Cadenas en UTF-8 modificado de Java (nulo en dos bytes, pares sustitutos).
+    fn string(&mut self) -> io::Result<String> {
+        let n = u16::from_be_bytes(self.take()?) as usize;
+        let b = self.data.get(self.pos..self.pos + n).ok_or_else(|| bad("NBT truncado"))?;
+        self.pos += n;
+        if let Ok(s) = std::str::from_utf8(b) { return Ok(s.to_string()); }
+        let mut units = Vec::with_capacity(n);
+        let mut i = 0;
+        while i < n {
+            let c = b[i] as u16;
+            let (u, step) = if c < 0x80 {
+                (c, 1)
+            } else if c & 0xe0 == 0xc0 && i + 1 < n {
+                ((c & 0x1f) << 6 | (b[i + 1] as u16 & 0x3f), 2)
+            } else if c & 0xf0 == 0xe0 && i + 2 < n {
+                ((c & 0x0f) << 12 | (b[i + 1] as u16 & 0x3f) << 6 | (b[i + 2] as u16 & 0x3f), 3)
+            } else {
+                (0xfffd, 1)
+            };
+            units.push(u);
+            i += step;
+        }
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    fn payload(&mut self, kind: u8, depth: usize) -> io::Result<Tag> {
+        if depth > 512 { return Err(bad("NBT demasiado anidado")); }
+        Ok(match kind {
+            0 => Tag::End,
+            1 => Tag::Byte(self.u8()? as i8),
+            2 => Tag::Short(self.i16()?),
+            3 => Tag::Int(self.i32()?),
+            4 => Tag::Long(self.i64()?),
+            5 => Tag::Float(f32::from_be_bytes(self.take()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.take()?)),
+            7 => {
+                let n = self.len()?;
+                Tag::ByteArray((0..n).map(|_| self.u8().map(|b| b as i8)).collect::<io::Result<_>>()?)
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let item = self.u8()?;
+                let n = self.len()?;
+                let mut items = Vec::with_capacity(n.min(4096));
+                for _ in 0..n { items.push(self.payload(item, depth + 1)?); }
+                Tag::List(items)
+            }
+            10 => {
+                let mut m = HashMap::new();
+                loop {
+                    let k = self.u8()?;
+                    if k == 0 { break; }
+                    let name = self.string()?;
+                    m.insert(name, self.payload(k, depth + 1)?);
+                }
+                Tag::Compound(m)
+            }
+            11 => {
+                let n = self.len()?;
+                Tag::IntArray((0..n).map(|_| self.i32()).collect::<io::Result<_>>()?)
+            }
+            12 => {
+                let n = self.len()?;
+                Tag::LongArray((0..n).map(|_| self.i64()).collect::<io::Result<_>>()?)
+            }
+            _ => return Err(bad("tipo de tag NBT desconocido")),
+        })
+    }
+}
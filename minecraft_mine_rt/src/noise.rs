@@ -0,0 +1,99 @@
+use crate::rng::Rng;
+
+/// Ruido de gradiente de Perlin (versión mejorada, 2002) con permutación por semilla.
+/// A diferencia del `fbm` de texturas, trabaja en coordenadas de mundo y devuelve [-1, 1].
+pub struct Perlin {
+    perm: [u8; 512],
+}
+
+fn fade(t: f32) -> f32 { t * t * t * (t * (t * 6.0 - 15.0) + 10.0) }
+fn lerp(a: f32, b: f32, t: f32) -> f32 { a + (b - a) * t }
+
+fn grad2(h: u8, x: f32, y: f32) -> f32 {
+    // 8 direcciones: ejes y diagonales
+    match h & 7 {
+        0 => x + y, 1 => -x + y, 2 => x - y, 3 => -x - y,
+        4 => x, 5 => -x, 6 => y, _ => -y,
+    }
+}
+
+fn grad3(h: u8, x: f32, y: f32, z: f32) -> f32 {
+    // las 12 aristas del cubo (con 4 repetidas para completar 16)
+    let h = h & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Self {
+        let mut p: [u8; 256] = std::array::from_fn(|i| i as u8);
+        let mut rng = Rng::new(seed);
+        for i in (1..256).rev() { p.swap(i, rng.below(i + 1)); }
+        let mut perm = [0u8; 512];
+        for i in 0..512 { perm[i] = p[i & 255]; }
+        Self { perm }
+    }
+
+    fn p(&self, i: i32) -> u8 { self.perm[(i & 511) as usize] }
+
+    pub fn noise2(&self, x: f32, y: f32) -> f32 {
+        let (xi, yi) = (x.floor() as i32 & 255, y.floor() as i32 & 255);
+        let (x, y) = (x - x.floor(), y - y.floor());
+        let (u, v) = (fade(x), fade(y));
+        let a = self.p(xi) as i32 + yi;
+        let b = self.p(xi + 1) as i32 + yi;
+        let n = lerp(
+            lerp(grad2(self.p(a), x, y), grad2(self.p(b), x - 1.0, y), u),
+            lerp(grad2(self.p(a + 1), x, y - 1.0), grad2(self.p(b + 1), x - 1.0, y - 1.0), u),
+            v,
+        );
+        n.clamp(-1.0, 1.0)
+    }
+
+    pub fn noise3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (xi, yi, zi) = (x.floor() as i32 & 255, y.floor() as i32 & 255, z.floor() as i32 & 255);
+        let (x, y, z) = (x - x.floor(), y - y.floor(), z - z.floor());
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        let a = self.p(xi) as i32 + yi;
+        let (aa, ab) = (self.p(a) as i32 + zi, self.p(a + 1) as i32 + zi);
+        let b = self.p(xi + 1) as i32 + yi;
+        let (ba, bb) = (self.p(b) as i32 + zi, self.p(b + 1) as i32 + zi);
+        let g = |h: i32, dx: f32, dy: f32, dz: f32| grad3(self.p(h), x - dx, y - dy, z - dz);
+        let n = lerp(
+            lerp(
+                lerp(g(aa, 0.0, 0.0, 0.0), g(ba, 1.0, 0.0, 0.0), u),
+                lerp(g(ab, 0.0, 1.0, 0.0), g(bb, 1.0, 1.0, 0.0), u),
+                v,
+            ),
+            lerp(
+                lerp(g(aa + 1, 0.0, 0.0, 1.0), g(ba + 1, 1.0, 0.0, 1.0), u),
+                lerp(g(ab + 1, 0.0, 1.0, 1.0), g(bb + 1, 1.0, 1.0, 1.0), u),
+                v,
+            ),
+            w,
+        );
+        n.clamp(-1.0, 1.0)
+    }
+
+    /// Suma fractal de `octaves` capas (lacunaridad 2, ganancia 0.5), normalizada a [-1, 1].
+    pub fn fbm2(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        let (mut f, mut amp, mut freq, mut total) = (0.0, 1.0, 1.0, 0.0);
+        for _ in 0..octaves.max(1) {
+            f += self.noise2(x * freq, y * freq) * amp;
+            total += amp;
+            freq *= 2.0; amp *= 0.5;
+        }
+        f / total
+    }
+
+    pub fn fbm3(&self, x: f32, y: f32, z: f32, octaves: u32) -> f32 {
+        let (mut f, mut amp, mut freq, mut total) = (0.0, 1.0, 1.0, 0.0);
+        for _ in 0..octaves.max(1) {
+            f += self.noise3(x * freq, y * freq, z * freq) * amp;
+            total += amp;
+            freq *= 2.0; amp *= 0.5;
+        }
+        f / total
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::block::BlockRegistry;
+use crate::color::Color;
+use crate::material::Material;
+use crate::math::{Mat4, Vec3};
+use crate::shape::{Surface, Triangle};
+use crate::texture::Texture;
+
+// Modelos Wavefront `.obj` con sus materiales `.mtl`: posiciones, UV y normales,
+// polígonos (triangulados recortando orejas), grupos `g`/`o` y `usemtl`.
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+/// Material de un `.mtl`, con los valores por defecto del formato.
+struct Mtl {
+    kd: Color,
+    ks: Option<Color>,
+    ke: Color,
+    ns: f32,
+    d: f32,
+    ni: f32,
+    /// Ruta de `map_Kd`, ya relativa al directorio del `.mtl`
+    map_kd: Option<String>,
+}
+
+struct Face {
+    p: [Vec3; 3],
+    n: Option<[Vec3; 3]>,
+    uv: [(f32, f32); 3],
+    mat: Option<String>,
+    /// Índice en `groups` (el último `g`/`o` antes de la cara)
+    group: usize,
+}
+
+pub struct ObjModel {
+    faces: Vec<Face>,
+    groups: Vec<Vec<String>>,
+    materials: HashMap<String, Mtl>,
+}
+
+fn nums<const N: usize>(args: &[&str], line: usize) -> io::Result<[f32; N]> {
+    let mut out = [0.0; N];
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = args.get(i).and_then(|s| s.parse().ok()).ok_or_else(|| bad(&format!("línea {}: faltan números", line)))?;
+    }
+    Ok(out)
+}
+
+/// Índice 1-based del archivo (negativo = contando desde el final) a 0-based.
+fn index(s: &str, len: usize, line: usize) -> io::Result<usize> {
+    let i: i64 = s.parse().map_err(|_| bad(&format!("línea {}: índice inválido '{}'", line, s)))?;
+    let i = if i < 0 { len as i64 + i } else { i - 1 };
+    if i < 0 || i as usize >= len { return Err(bad(&format!("línea {}: índice {} fuera de rango", line, s))); }
+    Ok(i as usize)
+}
+
+pub fn load(path: &str) -> io::Result<ObjModel> {
+    let src = fs::read_to_string(path)?;
+    let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+    let (mut v, mut vt, mut vn) = (Vec::new(), Vec::new(), Vec::new());
+    let mut model = ObjModel { faces: Vec::new(), groups: vec![vec!["default".to_string()]], materials: HashMap::new() };
+    let (mut mat, mut group) = (None, 0);
+
+    for (n, l) in src.lines().enumerate() {
+        let line = n + 1;
+        let mut it = l.split('#').next().unwrap_or("").split_whitespace();
+        let Some(cmd) = it.next() else { continue };
+        let args: Vec<&str> = it.collect();
+        match cmd {
+            "v" => { let [x, y, z] = nums(&args, line)?; v.push(Vec3::new(x, y, z)); }
+            "vt" => { let [s] = nums(&args, line)?; vt.push((s, args.get(1).and_then(|t| t.parse().ok()).unwrap_or(0.0))); }
+            "vn" => { let [x, y, z] = nums(&args, line)?; vn.push(Vec3::new(x, y, z).norm()); }
+            "g" | "o" => {
+                model.groups.push(args.iter().map(|s| s.to_string()).collect());
+                group = model.groups.len() - 1;
+            }
+            "usemtl" => mat = args.first().map(|s| s.to_string()),
+            "mtllib" => {
+                for file in &args {
+                    let p = dir.join(file);
+                    match load_mtl(&p) {
+                        Ok(m) => model.materials.extend(m),
+                        Err(e) => eprintln!("aviso: {}: {}", p.display(), e),
+                    }
+                }
+            }
+            "f" => {
+                // v, v/vt, v//vn o v/vt/vn
+                let mut corners = Vec::with_capacity(args.len());
+                for a in &args {
+                    let mut parts = a.split('/');
+                    let p = index(parts.next().unwrap_or(""), v.len(), line)?;
+                    let t = match parts.next() { Some(s) if !s.is_empty() => Some(index(s, vt.len(), line)?), _ => None };
+                    let nn = match parts.next() { Some(s) if !s.is_empty() => Some(index(s, vn.len(), line)?), _ => None };
+                    corners.push((p, t, nn));
+                }
+                if corners.len() < 3 { return Err(bad(&format!("línea {}: cara con menos de 3 vértices", line))); }
+                let points: Vec<Vec3> = corners.iter().map(|c| v[c.0]).collect();
+                for [a, b, c] in triangulate(&points) {
+                    let k = [corners[a], corners[b], corners[c]];
+                    let n = k.iter().all(|c| c.2.is_some()).then(|| k.map(|c| vn[c.2.unwrap()]));
+                    model.faces.push(Face {
+                        p: k.map(|c| v[c.0]),
+                        n,
+                        uv: k.map(|c| c.1.map_or((0.0, 0.0), |t| vt[t])),
+                        mat: mat.clone(),
+                        group,
+                    });
+                }
+            }
+            _ => {} // s, l, p y demás: sin efecto en el render
+        }
+    }
+    Ok(model)
+}
+
+fn load_mtl(path: &Path) -> io::Result<HashMap<String, Mtl>> {
+    let src = fs::read_to_string(path)?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut out = HashMap::new();
+    let mut cur: Option<(String, Mtl)> = None;
+    for (n, l) in src.lines().enumerate() {
+        let line = n + 1;
+        let mut it = l.split('#').next().unwrap_or("").split_whitespace();
+        let Some(cmd) = it.next() else { continue };
+        let args: Vec<&str> = it.collect();
+        if cmd == "newmtl" {
+            out.extend(cur.take());
+            let name = args.first().ok_or_else(|| bad(&format!("línea {}: newmtl sin nombre", line)))?;
+            let m = Mtl { kd: Color::splat(0.8), ks: None, ke: Color::black(), ns: 16.0, d: 1.0, ni: 1.0, map_kd: None };
+            cur = Some((name.to_string(), m));
+            continue;
+        }
+        let Some((_, m)) = cur.as_mut() else { continue };
+        let color = |args: &[&str]| nums::<3>(args, line).map(|[r, g, b]| Color::new(r, g, b));
+        match cmd {
+            "Kd" => m.kd = color(&args)?,
+            "Ks" => m.ks = Some(color(&args)?),
+            "Ke" => m.ke = color(&args)?,
+            "Ns" => m.ns = nums::<1>(&args, line)?[0],
+            "d" => m.d = nums::<1>(&args, line)?[0],
+            "Tr" => m.d = 1.0 - nums::<1>(&args, line)?[0],
+            "Ni" => m.ni = nums::<1>(&args, line)?[0],
+            // las opciones (-s, -o…) van antes; el archivo es lo último
+            "map_Kd" => m.map_kd = args.last().map(|f| dir.join(f).to_string_lossy().into_owned()),
+            _ => {}
+        }
+    }
+    out.extend(cur);
+    Ok(out)
+}
+
+/// Triángulos de un polígono plano (índices en `points`), recortando orejas sobre
+/// la proyección en el plano del polígono; si no encuentra ninguna, en abanico.
+fn triangulate(points: &[Vec3]) -> Vec<[usize; 3]> {
+    // normal de Newell, válida también para polígonos cóncavos
+    let n = (0..points.len()).fold(Vec3::default(), |acc, i| {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        acc.add(Vec3::new((a.y - b.y) * (a.z + b.z), (a.z - b.z) * (a.x + b.x), (a.x - b.x) * (a.y + b.y)))
+    });
+    let left = |a: usize, b: usize, c: usize| points[b].sub(points[a]).cross(points[c].sub(points[a])).dot(n);
+    let inside = |p: usize, [a, b, c]: [usize; 3]| left(a, b, p) > 0.0 && left(b, c, p) > 0.0 && left(c, a, p) > 0.0;
+
+    let mut ring: Vec<usize> = (0..points.len()).collect();
+    let mut tris = Vec::with_capacity(points.len() - 2);
+    while ring.len() > 3 {
+        let k = ring.len();
+        let ear = (0..k).find(|&i| {
+            let t = [ring[(i + k - 1) % k], ring[i], ring[(i + 1) % k]];
+            left(t[0], t[1], t[2]) > 0.0 && ring.iter().all(|&p| t.contains(&p) || !inside(p, t))
+        });
+        let Some(i) = ear else { break };
+        tris.push([ring[(i + k - 1) % k], ring[i], ring[(i + 1) % k]]);
+        ring.remove(i);
+    }
+    // lo que quede (el último triángulo o un polígono degenerado), en abanico
+    tris.extend((1..ring.len() - 1).map(|i| [ring[0], ring[i], ring[i + 1]]));
+    tris
+}
+
+impl ObjModel {
+    /// Triángulos llevados al mundo con `to_world`, solo de los grupos de `groups`
+    /// (todos si está vacío). Cada material del `.mtl` se registra como
+    /// `<name>:<material>` con su textura.
+    pub fn triangles(&self, reg: &mut BlockRegistry, name: &str, to_world: Mat4, groups: &[String]) -> Vec<Triangle> {
+        let normals = to_world.inverse().transpose();
+        let mut surfaces: HashMap<Option<&str>, Surface> = HashMap::new();
+        let mut tris = Vec::new();
+        for f in &self.faces {
+            if !groups.is_empty() && !self.groups[f.group].iter().any(|g| groups.contains(g)) { continue; }
+            let surface = surfaces.entry(f.mat.as_deref()).or_insert_with(|| self.surface(reg, name, f.mat.as_deref())).clone();
+            tris.push(Triangle {
+                p: f.p.map(|p| to_world.point(p)),
+                n: f.n.map(|n| n.map(|n| normals.dir(n).norm())),
+                uv: f.uv,
+                surface,
+            });
+        }
+        tris
+    }
+
+    /// `Kd` o `map_Kd` como textura, `Ks`/`Ns` como brillo, `d` como transparencia,
+    /// `Ni` como índice de refracción y `Ke` como emisión.
+    fn surface(&self, reg: &mut BlockRegistry, name: &str, mat: Option<&str>) -> Surface {
+        let key = format!("{}:{}", name, mat.unwrap_or("default"));
+        if let Some(mat_id) = reg.find_mat(&key) { return Surface { mat_id, face_tex: None, tint: None }; }
+        let m = mat.and_then(|k| self.materials.get(k));
+        if let Some(k) = mat && m.is_none() { eprintln!("aviso: material '{}' no está en el .mtl", k); }
+        let kd = m.map_or(Color::splat(0.8), |m| m.kd);
+        let tex = m.and_then(|m| m.map_kd.as_deref()).and_then(|file| {
+            let tex = Texture::from_file(file);
+            if tex.is_none() { eprintln!("aviso: no pude cargar la textura {}", file); }
+            tex
+        });
+        let tex_id = reg.add_texture(&key, tex.unwrap_or(Texture::Solid(kd)));
+        let material = match m {
+            Some(m) => Material {
+                tex_id,
+                albedo: 1.0,
+                specular: m.ks.map_or(0.1, |c| (c.r + c.g + c.b) / 3.0).clamp(0.0, 1.0),
+                transparency: (1.0 - m.d).clamp(0.0, 1.0),
+                reflectivity: if m.d < 1.0 { 0.05 } else { 0.0 },
+                ior: m.ni.max(1.0),
+                shininess: m.ns.max(1.0),
+                emissive: m.ke,
+            },
+            None => Material {
+                tex_id, albedo: 1.0, specular: 0.1, transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 16.0, emissive: Color::black(),
+            },
+        };
+        Surface { mat_id: reg.material(&key, material), face_tex: None, tint: None }
+    }
+}
@@ -0,0 +1,183 @@
+use std::fs;
+use std::io;
+
+use crate::zlib::{crc32, zlib_compress, zlib_decompress};
+
+// Lectura de PNG sin entrelazar (gris, RGB, paleta, con o sin alfa; 1 a 16 bits).
+// El resultado es RGB de 8 bits como las imágenes PPM; el alfa se descarta.
+// La escritura es RGB o RGBA de 8 bits.
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+/// Ancho, alto y píxeles RGB por filas, de arriba abajo.
+pub fn load(path: &str) -> io::Result<(usize, usize, Vec<u8>)> { decode(&fs::read(path)?) }
+
+/// Como `load`, con el archivo ya en memoria.
+pub fn decode(bytes: &[u8]) -> io::Result<(usize, usize, Vec<u8>)> {
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") { return Err(bad("no es un PNG")); }
+
+    let (mut header, mut palette, mut idat) = (None, Vec::new(), Vec::new());
+    let mut pos = 8;
+    while pos + 12 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+        let body = bytes.get(pos + 4..pos + 8 + len).ok_or_else(|| bad("chunk truncado"))?;
+        let crc = bytes.get(pos + 8 + len..pos + 12 + len).ok_or_else(|| bad("chunk sin CRC"))?;
+        if crc32(body) != u32::from_be_bytes(crc.try_into().unwrap()) { return Err(bad("CRC de chunk no coincide")); }
+        let (kind, data) = body.split_at(4);
+        match kind {
+            b"IHDR" if data.len() == 13 => header = Some(<[u8; 13]>::try_from(data).unwrap()),
+            b"PLTE" => palette = data.to_vec(),
+            b"IDAT" => idat.extend_from_slice(data),
+            b"IEND" => break,
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    let h = header.ok_or_else(|| bad("falta IHDR"))?;
+    let (w, ht) = (u32::from_be_bytes(h[0..4].try_into().unwrap()) as usize, u32::from_be_bytes(h[4..8].try_into().unwrap()) as usize);
+    let (depth, color) = (h[8] as usize, h[9]);
+    if h[12] != 0 { return Err(bad("PNG entrelazado no soportado")); }
+    let channels = match color { 0 => 1, 2 => 3, 3 => 1, 4 => 2, 6 => 4, _ => return Err(bad("tipo de color PNG inválido")) };
+    if !matches!(depth, 1 | 2 | 4 | 8 | 16) || (color != 0 && color != 3 && depth < 8) || (color == 3 && depth == 16) {
+        return Err(bad("profundidad de PNG inválida"));
+    }
+
+    // quitar los filtros por fila; `bpp` es la distancia al píxel de la izquierda
+    let raw = zlib_decompress(&idat)?;
+    let stride = (w * channels * depth).div_ceil(8);
+    let bpp = (channels * depth / 8).max(1);
+    if raw.len() < (stride + 1) * ht { return Err(bad("datos de imagen truncados")); }
+    let mut px = vec![0u8; stride * ht];
+    for y in 0..ht {
+        let filter = raw[y * (stride + 1)];
+        let src = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        let (done, row) = px.split_at_mut(y * stride);
+        let prev = if y > 0 { &done[(y - 1) * stride..] } else { &[][..] };
+        let row = &mut row[..stride];
+        for x in 0..stride {
+            let a = if x >= bpp { row[x - bpp] as i32 } else { 0 };
+            let b = prev.get(x).map_or(0, |&v| v as i32);
+            let c = if x >= bpp { prev.get(x - bpp).map_or(0, |&v| v as i32) } else { 0 };
+            let pred = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => (a + b) / 2,
+                4 => {
+                    // Paeth
+                    let p = a + b - c;
+                    let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+                    if pa <= pb && pa <= pc { a } else if pb <= pc { b } else { c }
+                }
+                _ => return Err(bad("filtro PNG inválido")),
+            };
+            row[x] = src[x].wrapping_add(pred as u8);
+        }
+    }
+
+    // muestra `i` de una fila a 8 bits (los de 16 bits se quedan con el byte alto)
+    let sample = |row: &[u8], i: usize| -> u8 {
+        match depth {
+            8 => row[i],
+            16 => row[i * 2],
+            _ => {
+                let bits = row[i * depth / 8] >> (8 - depth - i * depth % 8) & ((1 << depth) - 1) as u8;
+                if color == 3 { bits } else { (bits as u32 * 255 / ((1 << depth) - 1)) as u8 }
+            }
+        }
+    };
+    let mut rgb = Vec::with_capacity(w * ht * 3);
+    for row in px.chunks(stride) {
+        for x in 0..w {
+            let s = |c: usize| sample(row, x * channels + c);
+            match color {
+                0 | 4 => rgb.extend([s(0); 3]),
+                3 => {
+                    let i = s(0) as usize * 3;
+                    rgb.extend_from_slice(palette.get(i..i + 3).ok_or_else(|| bad("índice fuera de la paleta"))?);
+                }
+                _ => rgb.extend([s(0), s(1), s(2)]),
+            }
+        }
+    }
+    Ok((w, ht, rgb))
+}
+
+/// PNG de 8 bits con `channels` = 3 (RGB) o 4 (RGBA), filas de arriba abajo. Cada
+/// fila lleva el filtro que deja la menor suma de diferencias absolutas.
+pub fn encode(w: usize, h: usize, channels: usize, px: &[u8]) -> Vec<u8> {
+    let stride = w * channels;
+    let mut raw = Vec::with_capacity((stride + 1) * h);
+    let zeros = vec![0u8; stride];
+    for y in 0..h {
+        let row = &px[y * stride..(y + 1) * stride];
+        let prev = if y > 0 { &px[(y - 1) * stride..y * stride] } else { &zeros[..] };
+        let filtered = |f: u8| -> Vec<u8> {
+            (0..stride).map(|x| {
+                let a = if x >= channels { row[x - channels] as i32 } else { 0 };
+                let (b, c) = (prev[x] as i32, if x >= channels { prev[x - channels] as i32 } else { 0 });
+                let pred = match f {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => (a + b) / 2,
+                    _ => {
+                        let p = a + b - c;
+                        let (pa, pb, pc) = ((p - a).abs(), (p - b).abs(), (p - c).abs());
+                        if pa <= pb && pa <= pc { a } else if pb <= pc { b } else { c }
+                    }
+                };
+                row[x].wrapping_sub(pred as u8)
+            }).collect()
+        };
+        let cost = |r: &[u8]| r.iter().map(|&v| (v as i8).unsigned_abs() as u32).sum::<u32>();
+        let (f, best) = (0..5).map(|f| (f, filtered(f))).min_by_key(|(_, r)| cost(r)).unwrap();
+        raw.push(f);
+        raw.extend(best);
+    }
+
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut chunk = |kind: &[u8], data: &[u8]| {
+        out.extend((data.len() as u32).to_be_bytes());
+        let body = [kind, data].concat();
+        out.extend_from_slice(&body);
+        out.extend(crc32(&body).to_be_bytes());
+    };
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend((w as u32).to_be_bytes());
+    ihdr.extend((h as u32).to_be_bytes());
+    ihdr.extend([8, if channels == 4 { 6 } else { 2 }, 0, 0, 0]);
+    chunk(b"IHDR", &ihdr);
+    chunk(b"IDAT", &zlib_compress(&raw));
+    chunk(b"IEND", &[]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    #[test]
+    fn encode_decode_rgb() {
+        // degradado con ruido: cada fila acaba con un filtro distinto
+        let (w, h) = (37, 23);
+        let mut rng = Rng::new(7);
+        let px: Vec<u8> = (0..w * h * 3).map(|i| (i % 251) as u8 ^ if i % 5 == 0 { rng.next_u64() as u8 } else { 0 }).collect();
+        assert_eq!(decode(&encode(w, h, 3, &px)).unwrap(), (w, h, px));
+    }
+
+    #[test]
+    fn encode_decode_rgba_drops_alpha() {
+        let px: Vec<u8> = (0..4 * 3 * 4).map(|i| (i * 17) as u8).collect();
+        let rgb: Vec<u8> = px.chunks(4).flat_map(|p| p[..3].to_vec()).collect();
+        assert_eq!(decode(&encode(4, 3, 4, &px)).unwrap(), (4, 3, rgb));
+    }
+
+    #[test]
+    fn decode_rejects_bad_crc() {
+        let mut png = encode(2, 2, 3, &[9; 12]);
+        png[20] ^= 1; // dentro del IHDR
+        assert!(decode(&png).is_err());
+    }
+}
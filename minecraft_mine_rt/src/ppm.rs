@@ -3,17 +3,6 @@ use std::io::{self, Read, BufRead, BufReader};
 
 pub struct PpmImage { pub w:usize, pub h:usize, pub data:Vec<u8> } // RGB
 
-fn next_token<R:BufRead>(r:&mut R)->io::Result<String>{
-    let mut buf = String::new();
-    loop {
-        buf.clear();
-        let n = r.read_line(&mut buf)?;
-        if n==0 { return Ok(String::new()); }
-        let line = buf.trim();
-        if line.is_empty() || line.starts_with('#') { continue; }
-        return Ok(line.to_string());
-    }
-}
 
 pub fn load_ppm(path:&str)->io::Result<PpmImage>{
     let mut f = File::open(path)?;
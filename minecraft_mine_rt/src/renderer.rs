@@ -1,39 +1,135 @@
 use crate::aabb::{intersect_aabb, Aabb, Hit};
+use crate::bvh::Bvh;
+use crate::instance::Instance;
 use crate::color::Color;
 use crate::math::{reflect, refract, schlick, Ray, Vec3};
 use crate::material::Material;
+use crate::merge;
+use crate::shape::Shape;
 use crate::skybox::{sample_sky, CubeMap};
 use crate::texture::Texture;
 
 const MAX_DEPTH: u32 = 5;
 const BIAS: f32 = 1e-3;
 
+/// Luz puntual (antorchas, faroles) con caída 1/d².
+pub struct PointLight {
+    pub pos: Vec3,
+    pub color: Color,
+    pub intensity: f32,
+}
+
 pub struct Scene {
     pub cubes: Vec<Aabb>,
     pub materials: Vec<Material>,
     pub textures: Vec<Texture>,
     pub sun_dir: Vec3,
     pub sun_col: Color,
+    pub lights: Vec<PointLight>,
     pub sky_mix: f32,            // 0 = día, 1 = noche
     pub skybox: Option<CubeMap>, // cubemap opcional
+    pub time: f32,               // tiempo de animación (s) para texturas animadas
+    pub shutter: (f32, f32),     // apertura y cierre del obturador (s) respecto de `time`
+    pub instances: Vec<Instance>, // objetos girados o escalados, con su propia BVH
+    pub shapes: Vec<Shape>,      // esferas, planos, triángulos y mallas
+    pub bvh: Bvh,                // aceleración sobre `cubes`, `instances` y `shapes`; vacía = recorrido lineal
 }
 
 impl Scene {
+    /// (Re)construye la BVH; hay que llamarla de nuevo si cambian los cubos o las instancias,
+    /// y en cada cuadro si hay cubos en movimiento (abarcan su recorrido con el obturador abierto).
+    /// En los índices van los cubos, luego las instancias y por último las formas.
+    pub fn build_bvh(&mut self) {
+        let (open, close) = (self.time + self.shutter.0, self.time + self.shutter.1);
+        let swept = |c: &Aabb| match c.motion {
+            Some(v) => {
+                let (a, b) = (v.mul(open), v.mul(close));
+                (c.min.add(Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z))), c.max.add(Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z))))
+            }
+            None => (c.min, c.max),
+        };
+        let bounds: Vec<(Vec3, Vec3)> = self.cubes.iter().map(swept)
+            .chain(self.instances.iter().map(|o| (o.min, o.max)))
+            .chain(self.shapes.iter().map(Shape::bounds))
+            .collect();
+        self.bvh = Bvh::build(&bounds);
+    }
+
+    /// Quita los bloques ocultos y fusiona los vecinos iguales; antes de `build_bvh`.
+    /// Los cubos en movimiento no se fusionan ni tapan a nadie: quedan al final.
+    pub fn optimize(&mut self) -> merge::Stats {
+        let (moving, still): (Vec<Aabb>, Vec<Aabb>) = std::mem::take(&mut self.cubes).into_iter().partition(|c| c.motion.is_some());
+        let (mut cubes, mut stats) = merge::optimize(still, &self.materials, &self.textures);
+        (stats.before, stats.after) = (stats.before + moving.len(), stats.after + moving.len());
+        cubes.extend(moving);
+        self.cubes = cubes;
+        stats
+    }
+
+    /// Intersección con un cubo respetando las siluetas recortadas (pasto, flores):
+    /// en un texel transparente no hay choque, y la normal mira siempre hacia el rayo.
+    fn hit_cube(&self, ray: &Ray, c: &Aabb) -> Option<Hit> {
+        let mut h = intersect_aabb(ray, c)?;
+        let tex_id = h.face_tex.map_or(self.materials[h.mat_id].tex_id, |f| f[h.face_idx as usize]);
+        if let Texture::Plant { .. } = self.textures[tex_id] {
+            if !self.textures[tex_id].covers(h.u, h.v) { return None; }
+            if h.n.dot(ray.d) > 0.0 { h.n = h.n.mul(-1.0); }
+        }
+        Some(h)
+    }
+
+    /// Las formas sin espesor se ven por las dos caras: si no son transparentes,
+    /// la normal se gira hacia el rayo.
+    fn hit_shape(&self, ray: &Ray, s: &Shape) -> Option<Hit> {
+        let mut h = s.hit(ray)?;
+        if self.materials[h.mat_id].transparency <= 0.0 && h.n.dot(ray.d) > 0.0 { h.n = h.n.mul(-1.0); }
+        Some(h)
+    }
+
+    /// Primitiva `i`: los cubos, las instancias y después las formas.
+    fn hit_item(&self, ray: &Ray, i: usize) -> Option<Hit> {
+        let (n, m) = (self.cubes.len(), self.instances.len());
+        if i < n {
+            self.hit_cube(ray, &self.cubes[i])
+        } else if i < n + m {
+            self.instances[i - n].hit(ray, |r, c| self.hit_cube(r, c))
+        } else {
+            self.hit_shape(ray, &self.shapes[i - n - m])
+        }
+    }
+
     pub fn hit(&self, ray: &Ray) -> Option<Hit> {
         let mut best: Option<Hit> = None;
-        for c in &self.cubes {
-            if let Some(h) = intersect_aabb(ray, c) {
-                if best.as_ref().map_or(true, |b| h.t < b.t) {
-                    best = Some(h);
-                }
+        if !self.bvh.is_empty() {
+            // a igual distancia gana el cubo de menor índice, como en el recorrido lineal
+            let mut best_i = usize::MAX;
+            self.bvh.visit(ray, |i| {
+                let h = self.hit_item(ray, i)?;
+                if best.as_ref().is_some_and(|b| b.t < h.t || (b.t == h.t && best_i < i)) { return None; }
+                let t = h.t;
+                best = Some(h);
+                best_i = i;
+                Some(t)
+            });
+            return best;
+        }
+        for i in 0..self.cubes.len() + self.instances.len() + self.shapes.len() {
+            if let Some(h) = self.hit_item(ray, i)
+                && best.as_ref().is_none_or(|b| h.t < b.t)
+            {
+                best = Some(h);
             }
         }
         best
     }
 }
 
-pub fn trace(scene: &Scene, ray: Ray) -> Color {
-    trace_rec(scene, ray, MAX_DEPTH)
+/// Color del rayo y cobertura: 1 si choca con la escena, 0 si ve el cielo.
+pub fn trace(scene: &Scene, ray: Ray) -> (Color, f32) {
+    match scene.hit(&ray) {
+        Some(h) => (shade(scene, &ray, h, MAX_DEPTH), 1.0),
+        None => (env(scene, ray.d), 0.0),
+    }
 }
 
 fn env(scene: &Scene, dir: Vec3) -> Color {
@@ -45,90 +141,111 @@ fn trace_rec(scene: &Scene, ray: Ray, depth: u32) -> Color {
         return env(scene, ray.d);
     }
 
-    if let Some(h) = scene.hit(&ray) {
-        let m = &scene.materials[h.mat_id];
-
-        // textura por cara si existe
-        let tex_id = if let Some(faces) = &h.face_tex {
-            faces[h.face_idx as usize]
-        } else {
-            m.tex_id
-        };
-
-        // UVs envueltos
-        let mut u = h.u.fract().abs();
-        let mut v = h.v.fract().abs();
-        // La lateral de grass iba invertida en Y (índice 1 = grass_side)
-        if tex_id == 1 { v = 1.0 - v; }
-
-        let base = scene.textures[tex_id].sample(u, v).mul(m.albedo);
-
-        // Luz direccional (Lambert) teñida con el color del sol
-        let n = h.n;
-        let l = scene.sun_dir.mul(-1.0).norm();
-        let vdir = ray.d.mul(-1.0).norm();
-        let hvec = (l.add(vdir)).norm();
-
-        let ndl = 0.0_f32.max(n.dot(l));
-        let diff = base.hadamard(scene.sun_col).mul(ndl);
-
-        // Blinn-Phong sencillo
-        let spec = m.specular * 0.0_f32.max(n.dot(hvec)).powf(m.shininess);
-        let spec_col = scene.sun_col.mul(spec);
-
-        // Sombras duras pero no tan negras (deja pasar algo de luz indirecta)
-        let shadow_fac = {
-            let shadow_ray = Ray { o: h.p.add(n.mul(BIAS)), d: l };
-            if let Some(sh) = scene.hit(&shadow_ray) {
-                if sh.t > BIAS && sh.t < 100.0 { 0.55 } else { 1.0 }
-            } else { 1.0 }
-        };
+    match scene.hit(&ray) {
+        Some(h) => shade(scene, &ray, h, depth),
+        None => env(scene, ray.d),
+    }
+}
 
-        // “Skylight” simple: color del cielo por arriba, hace de luz ambiente
-        let sky_col = env(scene, Vec3::new(0.0, 1.0, 0.0));
-        // Más día => más ambiente; de noche baja pero nunca a cero
-        let amb_k = (0.25 * (1.0 - scene.sky_mix)) + (0.12 * scene.sky_mix);
-        let ambient = base.hadamard(sky_col).mul(amb_k);
-
-        // Emisivo (lava) sube un poco de noche
-        let emis = m.emissive.mul(1.0 + scene.sky_mix * 1.5);
-
-        // Fresnel para mezclar reflexión / refracción
-        let i = ray.d;
-        let front_face = n.dot(i) < 0.0;
-        let (n1, n2, n_use) = if front_face { (1.0, m.ior, n) } else { (m.ior, 1.0, n.mul(-1.0)) };
-        let cosi = (-i.dot(n_use)).clamp(-1.0, 1.0);
-        let mut kr = schlick(cosi, n1, n2) * m.reflectivity;
-        let kt = m.transparency * (1.0 - kr);
-
-        // Local = difuso + especular + ambiente + emisivo
-        let local = diff.mul(shadow_fac).add(spec_col).add(ambient).add(emis);
-
-        // Reflexión: si se agota profundidad, toma el entorno
-        let refl_col = if kr > 0.0 {
-            let rdir = reflect(i, n).norm();
-            let rayo = Ray { o: h.p.add(n.mul(BIAS)), d: rdir };
-            let c = if depth > 1 { trace_rec(scene, rayo, depth - 1) } else { env(scene, rdir) };
-            c.mul(kr)
-        } else { Color::black() };
-
-        // Refracción: igual, con leve atenuación azulada (agua/vidrio)
-        let refr_col = if kt > 0.0 {
-            let eta = n1 / n2;
-            if let Some(tdir) = refract(i, n_use, eta) {
-                let tdir = tdir.norm();
-                let rayo = Ray { o: h.p.sub(n.mul(BIAS)), d: tdir };
-                let through = if depth > 1 { trace_rec(scene, rayo, depth - 1) } else { env(scene, tdir) };
-                through.hadamard(Color::new(0.96, 0.98, 0.99)).mul(kt)
-            } else {
-                Color::black()
-            }
-        } else { Color::black() };
+/// Color en el punto `h` donde choca `ray`, con `depth` rebotes por delante.
+fn shade(scene: &Scene, ray: &Ray, h: Hit, depth: u32) -> Color {
+    let m = &scene.materials[h.mat_id];
 
-        // Factor local para que el balance cierre
-        let local_fac = (1.0 - m.reflectivity - m.transparency).clamp(0.0, 1.0);
-        local.mul(local_fac).add(refl_col).add(refr_col)
+    // textura por cara si existe
+    let tex_id = if let Some(faces) = &h.face_tex {
+        faces[h.face_idx as usize]
     } else {
-        env(scene, ray.d)
+        m.tex_id
+    };
+
+    // UVs envueltos
+    let u = h.u.fract().abs();
+    let v = h.v.fract().abs();
+
+    let mut base = scene.textures[tex_id].sample(u, v, scene.time).mul(m.albedo);
+    // tinte de bioma (césped, hojas) en las caras marcadas
+    if let Some(tint) = h.tint
+        && tint.faces & (1 << h.face_idx) != 0
+    {
+        base = base.hadamard(tint.color);
+    }
+
+    // Luz direccional (Lambert) teñida con el color del sol
+    let n = h.n;
+    let l = scene.sun_dir.mul(-1.0).norm();
+    let vdir = ray.d.mul(-1.0).norm();
+    let hvec = (l.add(vdir)).norm();
+
+    let ndl = 0.0_f32.max(n.dot(l));
+    let diff = base.hadamard(scene.sun_col).mul(ndl);
+
+    // Blinn-Phong sencillo
+    let spec = m.specular * 0.0_f32.max(n.dot(hvec)).powf(m.shininess);
+    let spec_col = scene.sun_col.mul(spec);
+
+    // Sombras duras pero no tan negras (deja pasar algo de luz indirecta)
+    let shadow_fac = {
+        let shadow_ray = Ray { o: h.p.add(n.mul(BIAS)), d: l, time: ray.time };
+        if let Some(sh) = scene.hit(&shadow_ray) {
+            if sh.t > BIAS && sh.t < 100.0 { 0.55 } else { 1.0 }
+        } else { 1.0 }
+    };
+
+    // Luces puntuales con sombra dura
+    let mut lamps = Color::black();
+    for light in &scene.lights {
+        let to_l = light.pos.sub(h.p);
+        let dist = to_l.len().max(1e-4);
+        let ld = to_l.mul(1.0 / dist);
+        let ndl = n.dot(ld);
+        if ndl <= 0.0 { continue; }
+        let shadow_ray = Ray { o: h.p.add(n.mul(BIAS)), d: ld, time: ray.time };
+        if scene.hit(&shadow_ray).is_some_and(|sh| sh.t > BIAS && sh.t < dist) { continue; }
+        lamps = lamps.add(base.hadamard(light.color).mul(light.intensity * ndl / (dist * dist)));
     }
+
+    // “Skylight” simple: color del cielo por arriba, hace de luz ambiente
+    let sky_col = env(scene, Vec3::new(0.0, 1.0, 0.0));
+    // Más día => más ambiente; de noche baja pero nunca a cero
+    let amb_k = (0.25 * (1.0 - scene.sky_mix)) + (0.12 * scene.sky_mix);
+    let ambient = base.hadamard(sky_col).mul(amb_k);
+
+    // Emisivo (lava) sube un poco de noche
+    let emis = m.emissive.mul(1.0 + scene.sky_mix * 1.5);
+
+    // Fresnel para mezclar reflexión / refracción
+    let i = ray.d;
+    let front_face = n.dot(i) < 0.0;
+    let (n1, n2, n_use) = if front_face { (1.0, m.ior, n) } else { (m.ior, 1.0, n.mul(-1.0)) };
+    let cosi = (-i.dot(n_use)).clamp(-1.0, 1.0);
+    let kr = schlick(cosi, n1, n2) * m.reflectivity;
+    let kt = m.transparency * (1.0 - kr);
+
+    // Local = difuso + especular + ambiente + emisivo
+    let local = diff.mul(shadow_fac).add(spec_col).add(lamps).add(ambient).add(emis);
+
+    // Reflexión: si se agota profundidad, toma el entorno
+    let refl_col = if kr > 0.0 {
+        let rdir = reflect(i, n).norm();
+        let rayo = Ray { o: h.p.add(n.mul(BIAS)), d: rdir, time: ray.time };
+        let c = if depth > 1 { trace_rec(scene, rayo, depth - 1) } else { env(scene, rdir) };
+        c.mul(kr)
+    } else { Color::black() };
+
+    // Refracción: igual, con leve atenuación azulada (agua/vidrio)
+    let refr_col = if kt > 0.0 {
+        let eta = n1 / n2;
+        if let Some(tdir) = refract(i, n_use, eta) {
+            let tdir = tdir.norm();
+            let rayo = Ray { o: h.p.sub(n.mul(BIAS)), d: tdir, time: ray.time };
+            let through = if depth > 1 { trace_rec(scene, rayo, depth - 1) } else { env(scene, tdir) };
+            through.hadamard(Color::new(0.96, 0.98, 0.99)).mul(kt)
+        } else {
+            Color::black()
+        }
+    } else { Color::black() };
+
+    // Factor local para que el balance cierre
+    let local_fac = (1.0 - m.reflectivity - m.transparency).clamp(0.0, 1.0);
+    local.mul(local_fac).add(refl_col).add(refr_col)
 }
@@ -1,18 +1,38 @@
 use crate::aabb::Aabb;
+use crate::biome::Biome;
+use crate::block::BlockRegistry;
+use crate::bvh::Bvh;
+use crate::camera::Camera;
 use crate::color::Color;
-use crate::material::Material;
-use crate::math::Vec3;
-use crate::renderer::Scene;
+use crate::instance::Instance;
+use crate::math::{Mat4, Quat, Vec3};
+use crate::obj;
+use crate::renderer::{PointLight, Scene};
+use crate::shape::{Mesh, Shape, Surface, Triangle};
 use crate::skybox::CubeMap;
-use crate::texture::{TexKind, Texture};
+use crate::terrain::{self, Params};
+use crate::structures;
+use crate::vegetation;
+use crate::world::{BlockBox, World};
 
+/// Órbita de la cámara: una vuelta completa mientras `t` va de 0 a 1.
 pub struct Anim {
-    pub angle: f32,
     pub radius: f32,
+    /// Cuánto se aleja a mitad de vuelta
+    pub wobble: f32,
     pub eye_h: f32,
-    pub sky_mix: f32,
+    pub target: Vec3,
 }
-.
+
+impl Anim {
+    pub fn camera(&self, t: f32, aspect: f32) -> Camera {
+        let angle = std::f32::consts::PI * 2.0 * t;
+        let radius = self.radius + self.wobble * (0.5 - angle.cos() * 0.5);
+        let eye = Vec3::new(self.target.x + radius * angle.cos(), self.eye_h, self.target.z + radius * angle.sin());
+        Camera::new(eye, self.target, Vec3::new(0.0, 1.0, 0.0), 60.0, aspect)
+    }
+}
+
 fn height(x: i32, z: i32) -> i32 {
     let xf = x as f32;
     let zf = z as f32;
@@ -32,62 +52,67 @@ fn height(x: i32, z: i32) -> i32 {
     (base + h1 + h2).round() as i32 
 }
 
-fn push_block(cubes: &mut Vec<Aabb>, x: f32, y: f32, z: f32, mat_id: usize, face_tex: [usize; 6]) {
-    cubes.push(Aabb {
-        min: Vec3::new(x, y, z),
-        max: Vec3::new(x + 1.0, y + 1.0, z + 1.0),
-        mat_id,
-        face_tex: Some(face_tex),
-    });
+/// Bipirámide de seis caras de largo `len` desde `base` a lo largo de `axis`.
+fn crystal(base: Vec3, axis: Vec3, len: f32, r: f32, surface: &Surface) -> Vec<Triangle> {
+    // anillo a un tercio del largo, sobre dos ejes perpendiculares a `axis`
+    let side = axis.cross(Vec3::new(0.0, 0.0, 1.0)).norm();
+    let other = axis.cross(side);
+    let mid = base.add(axis.mul(len * 0.3));
+    let ring: Vec<Vec3> = (0..6).map(|i| {
+        let a = i as f32 * std::f32::consts::TAU / 6.0;
+        mid.add(side.mul(a.cos() * r)).add(other.mul(a.sin() * r))
+    }).collect();
+    let mut tris = Vec::new();
+    for i in 0..6 {
+        let (a, b) = (ring[i], ring[(i + 1) % 6]);
+        for apex in [base, base.add(axis.mul(len))] {
+            // orden de vértices con la normal hacia fuera
+            let mut p = [a, b, apex];
+            if b.sub(a).cross(apex.sub(a)).dot(a.add(b).mul(0.5).sub(mid)) < 0.0 { p.swap(0, 1); }
+            tris.push(Triangle { p, n: None, uv: [(0.0, 0.0), (1.0, 0.0), (0.5, 1.0)], surface: surface.clone() });
+        }
+    }
+    tris
 }
 
-pub fn build_scene_minecraft(t: f32) -> (Scene, Anim) {
-    // ciclo de sol
+/// Ciclo de sol: dirección, color y mezcla día/noche del cielo.
+fn sun_cycle(t: f32) -> (Vec3, Color, f32) {
     let elev = (std::f32::consts::PI * 2.0 * t).sin() * 0.6;
     let az = std::f32::consts::PI * 2.0 * t;
     let sun_dir = Vec3::new(az.cos(), elev, az.sin()).norm();
     let dayness = (elev * 1.2).clamp(0.0, 1.0);
     let sky_mix = 1.0 - dayness;
-    let sun_col = Color::new(1.0, 0.95, 0.85).mul(0.9 + 0.3 * dayness); 
-
-    // carga de texturas (con previsión de fallos)
-    let tx = |name: &str, kind: TexKind| {
-        Texture::from_ppm(&format!("assets/textures/{}.ppm", name)).unwrap_or(Texture::new(kind))
-    };
-    let textures = vec![
-        tx("grass_top",  TexKind::GrassTop),  // 0
-        tx("grass_side", TexKind::GrassSide), // 1
-        tx("dirt",       TexKind::Dirt),      // 2
-        tx("cobble",     TexKind::Cobble),    // 3
-        tx("planks",     TexKind::Wood),      // 4
-        tx("leaves",     TexKind::Leaves),    // 5
-        tx("glass",      TexKind::Glass),     // 6
-        tx("stone",      TexKind::Stone),     // 7
-        tx("water",      TexKind::Water),     // 8
-        tx("lava",       TexKind::Lava),      // 9
-        Texture::new(TexKind::Metal),         // 10
-    ];
-
-    // materiales
-    let mat_grass = Material { tex_id: 1, albedo: 1.0, specular: 0.05, transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 16.0, emissive: Color::black() };
-    let mat_dirt  = Material { tex_id: 2, albedo: 1.0, specular: 0.02, transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 8.0,  emissive: Color::black() };
-    let mat_cobb  = Material { tex_id: 3, albedo: 1.0, specular: 0.1,  transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 16.0, emissive: Color::black() };
-    let mat_wood  = Material { tex_id: 4, albedo: 1.0, specular: 0.05, transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 16.0, emissive: Color::black() };
-    let mat_leaf  = Material { tex_id: 5, albedo: 0.95, specular: 0.05, transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 8.0,  emissive: Color::black() };
-    let mat_glass = Material { tex_id: 6, albedo: 0.98, specular: 0.2,  transparency: 0.85, reflectivity: 0.05, ior: 1.5, shininess: 32.0, emissive: Color::black() };
-    let mat_stone = Material { tex_id: 7, albedo: 1.0, specular: 0.1,  transparency: 0.0, reflectivity: 0.0, ior: 1.0, shininess: 16.0, emissive: Color::black() };
-    let mat_water = Material { tex_id: 8, albedo: 0.98, specular: 0.5,  transparency: 0.9,  reflectivity: 0.05, ior: 1.33, shininess: 32.0, emissive: Color::black() };
-    let mat_lava  = Material { tex_id: 9, albedo: 1.0, specular: 0.2,  transparency: 0.0,  reflectivity: 0.0, ior: 1.0,  shininess: 8.0,  emissive: Color::new(1.8, 0.6, 0.1) };
-    let mat_metal = Material { tex_id:10, albedo: 1.0, specular: 0.9,  transparency: 0.0,  reflectivity: 0.7, ior: 1.0,  shininess: 64.0, emissive: Color::black() };
-
-    let materials = vec![mat_grass, mat_dirt, mat_cobb, mat_wood, mat_leaf, mat_glass, mat_stone, mat_water, mat_lava, mat_metal];
+    let sun_col = Color::new(1.0, 0.95, 0.85).mul(0.9 + 0.3 * dayness);
+    (sun_dir, sun_col, sky_mix)
+}
 
-    let grass_faces = [1, 1, 2, 0, 1, 1];               // césped con top/side/dirt
-    let one_tex = |tid: usize| [tid, tid, tid, tid, tid, tid];
+pub fn build_scene_minecraft(t: f32, biome: Biome) -> (Scene, Anim) {
+    let (sun_dir, sun_col, sky_mix) = sun_cycle(t);
+
+    // texturas, materiales y tipos de bloque
+    let mut reg = BlockRegistry::minecraft();
+    reg.biome = biome;
+    let grass = reg.id("grass");
+    let dirt = reg.id("dirt");
+    let cobble = reg.id("cobble");
+    let planks = reg.id("planks");
+    let log = reg.id("log");
+    let leaves = reg.id("leaves");
+    let glass = reg.id("glass");
+    let ridge = reg.id("stone_slab");
+    let stairs_s = reg.id("cobble_stairs[facing=south,half=bottom]");
+    let stairs_n = reg.id("cobble_stairs[facing=north,half=bottom]");
+    let fence = reg.id("oak_fence");
+    let wall_torch = reg.id("wall_torch[facing=north]");
+    let water = reg.id("water");
+    let lava = reg.id("lava");
+    let metal = reg.id("metal");
 
     // --- Terreno 16x16  ---
+    // los bloques enteros van a una rejilla editable; las piezas finas, directas a la lista
     let mut cubes: Vec<Aabb> = Vec::new();
     let size = 16;
+    let mut world = World::new(size, size, size).expect("16³ bloques caben en la rejilla");
 
     let lake_min = (10, 2);
     let lake_max = (15, 6);
@@ -102,88 +127,144 @@ pub fn build_scene_minecraft(t: f32) -> (Scene, Anim) {
             let in_lava = x >= lava_min.0 && x <= lava_max.0 && z >= lava_min.1 && z <= lava_max.1;
 
             // altura del terreno
-            let mut h = height(x as i32, z as i32);
+            let mut h = height(x, z);
             if in_lake { h = 1; } 
             if in_lava { h = 1; } 
 
-            // capas de tierra
-            for y in 0..(h - 1).max(0) {
-                push_block(&mut cubes, x as f32, y as f32, z as f32, 1, one_tex(2));
-            }
-            // bloque superior
-            push_block(&mut cubes, x as f32, (h - 1).max(0) as f32, z as f32, 0, grass_faces);
+            // capas de tierra y bloque superior
+            let top = (h - 1).max(0);
+            if top > 0 { world.fill(BlockBox::new([x, 0, z], [x, top - 1, z]), Some(dirt)); }
+            world.set_block(x, top, z, Some(grass));
         }
     }
 
+    // muro de piedra junto al camino que se levanta a lo largo de la vuelta
+    let built = (t * size as f32).round() as i32;
+    for x in 0..built {
+        let y = height(x, 7);
+        world.fill(BlockBox::new([x, y, 7], [x, y + (x % 3 != 1) as i32, 7]), Some(cobble));
+    }
+
     //  Lago
-    cubes.push(Aabb {
-        min: Vec3::new(lake_min.0 as f32, 1.0, lake_min.1 as f32),
-        max: Vec3::new(lake_max.0 as f32, 1.08, lake_max.1 as f32),
-        mat_id: 7, // water
-        face_tex: Some(one_tex(8)),
-    });
+    cubes.push(reg.aabb(
+        water,
+        Vec3::new(lake_min.0 as f32, 1.0, lake_min.1 as f32),
+        Vec3::new(lake_max.0 as f32, 1.08, lake_max.1 as f32),
+    ));
 
     // Lava
-    cubes.push(Aabb {
-        min: Vec3::new(lava_min.0 as f32, 1.0, lava_min.1 as f32),
-        max: Vec3::new((lava_max.0 + 1) as f32, 1.06, (lava_max.1 + 1) as f32),
-        mat_id: 8, // lava
-        face_tex: Some(one_tex(9)),
-    });
+    cubes.push(reg.aabb(
+        lava,
+        Vec3::new(lava_min.0 as f32, 1.0, lava_min.1 as f32),
+        Vec3::new((lava_max.0 + 1) as f32, 1.06, (lava_max.1 + 1) as f32),
+    ));
 
     // Camino
     for x in 0..size {
-        cubes.push(Aabb {
-            min: Vec3::new(x as f32, 1.0, 8.0),
-            max: Vec3::new(x as f32 + 1.0, 1.1, 9.0),
-            mat_id: 2,
-            face_tex: Some(one_tex(3)),
-        });
+        cubes.push(reg.aabb(cobble, Vec3::new(x as f32, 1.0, 8.0), Vec3::new(x as f32 + 1.0, 1.1, 9.0)));
     }
 
     let bx = 2; let bz = 9;        // esquina inferior-izquierda de la casa
-    let base_h = height(bx as i32, bz as i32) as f32; // altura de referencia
+    let base_h = height(bx, bz) as f32; // altura de referencia
     let base = Vec3::new(bx as f32, base_h, bz as f32);
+    // caja relativa a la esquina de la casa
+    let part = |id, min: Vec3, max: Vec3| reg.aabb(id, base.add(min), base.add(max));
 
     for z in 0..5 {
         for x in 0..5 {
-            let gh = height((bx + x) as i32, (bz + z) as i32) as i32;
-            let top = base_h as i32;
-            for y in gh..top {
-                push_block(&mut cubes, (bx + x) as f32, y as f32, (bz + z) as f32, 1, one_tex(2));
-            }
+            let (gh, top) = (height(bx + x, bz + z), base_h as i32);
+            if gh < top { world.fill(BlockBox::new([bx + x, gh, bz + z], [bx + x, top - 1, bz + z]), Some(dirt)); }
         }
     }
 
     for z in 0..5 {
         for y in 0..4 {
-            cubes.push(Aabb { min: base.add(Vec3::new(0.0, y as f32, z as f32)),  max: base.add(Vec3::new(0.5, y as f32 + 1.0, z as f32 + 1.0)), mat_id: 3, face_tex: Some(one_tex(4)) });
-            cubes.push(Aabb { min: base.add(Vec3::new(4.5, y as f32, z as f32)), max: base.add(Vec3::new(5.0, y as f32 + 1.0, z as f32 + 1.0)), mat_id: 3, face_tex: Some(one_tex(4)) });
+            let (y, z) = (y as f32, z as f32);
+            cubes.push(part(planks, Vec3::new(0.0, y, z), Vec3::new(0.5, y + 1.0, z + 1.0)));
+            cubes.push(part(planks, Vec3::new(4.5, y, z), Vec3::new(5.0, y + 1.0, z + 1.0)));
         }
     }
     
     for x in 0..5 {
         for y in 0..4 {
-            cubes.push(Aabb { min: base.add(Vec3::new(x as f32, y as f32, 0.0)),  max: base.add(Vec3::new(x as f32 + 1.0, y as f32 + 1.0, 0.5)),   mat_id: 3, face_tex: Some(one_tex(4)) });
-            cubes.push(Aabb { min: base.add(Vec3::new(x as f32, y as f32, 4.5)),  max: base.add(Vec3::new(x as f32 + 1.0, y as f32 + 1.0, 5.0)),   mat_id: 3, face_tex: Some(one_tex(4)) });
+            // puerta de dos bloques en el centro de la pared que da al camino
+            let door = x == 2 && y < 2;
+            let (x, y) = (x as f32, y as f32);
+            if !door { cubes.push(part(planks, Vec3::new(x, y, 0.0), Vec3::new(x + 1.0, y + 1.0, 0.5))); }
+            cubes.push(part(planks, Vec3::new(x, y, 4.5), Vec3::new(x + 1.0, y + 1.0, 5.0)));
         }
     }
-    // ventanas
-    cubes.push(Aabb { min: base.add(Vec3::new(2.0, 1.0, 0.0)),  max: base.add(Vec3::new(3.0, 2.0, 0.5)),  mat_id: 5, face_tex: Some(one_tex(6)) });
-    cubes.push(Aabb { min: base.add(Vec3::new(2.0, 1.0, 4.5)),  max: base.add(Vec3::new(3.0, 2.0, 5.0)),  mat_id: 5, face_tex: Some(one_tex(6)) });
-    // techo de piedra
-    for z in 0..5 {
-        for x in 0..5 {
-            cubes.push(Aabb { min: base.add(Vec3::new(x as f32, 4.0, z as f32)), max: base.add(Vec3::new(x as f32 + 1.0, 4.5, z as f32 + 1.0)), mat_id: 6, face_tex: Some(one_tex(7)) });
+    // ventana
+    cubes.push(part(glass, Vec3::new(2.0, 1.0, 4.5), Vec3::new(3.0, 2.0, 5.0)));
+    // tejado a dos aguas: escaleras de piedra que suben hacia la cumbrera en z = 2
+    let top = base_h as i32 + 4;
+    for (y, z0, z1, fill) in [(top, 0, 4, planks), (top + 1, 1, 3, planks), (top + 2, 2, 2, ridge)] {
+        world.fill(BlockBox::new([bx, y, bz + z0], [bx + 4, y, bz + z1]), Some(fill));
+        if z0 < z1 {
+            world.fill(BlockBox::new([bx, y, bz + z0], [bx + 4, y, bz + z0]), Some(stairs_s));
+            world.fill(BlockBox::new([bx, y, bz + z1], [bx + 4, y, bz + z1]), Some(stairs_n));
         }
     }
+    // antorchas a los lados de la puerta, en la pared que da al camino
+    let torch_at = [bx + 1, bx + 3].map(|x| [x, base_h as i32 + 2, bz - 1]);
+    for [x, y, z] in torch_at { world.set_block(x, y, z, Some(wall_torch)); }
+    // valla en L junto a la casa
+    for [x, z] in [[8, 9], [8, 10], [8, 11], [8, 12], [9, 12], [10, 12]] { world.set_block(x, height(x, z), z, Some(fence)); }
     // bloque metálico reflectivo delante de la casa
-    cubes.push(Aabb {
-        min: base.add(Vec3::new(5.5, 0.0, 1.5)),
-        max: base.add(Vec3::new(6.5, 1.0, 2.5)),
-        mat_id: 9,                 
-        face_tex: Some(one_tex(10)),
-    });
+    cubes.push(part(metal, Vec3::new(5.5, 0.0, 1.5), Vec3::new(6.5, 1.0, 2.5)));
+
+    // objetos girados: cartel que da vueltas, vagoneta descarrilada y marco con un objeto
+    let px = 1.0 / 16.0;
+    let mut instances = Vec::new();
+    let board = |id, lo: Vec3, hi: Vec3| { let mut c = reg.aabb(id, lo, hi); c.tiled = true; c };
+    let sign = vec![
+        board(log, Vec3::new(-px, 0.0, -px), Vec3::new(px, 1.1, px)),
+        board(planks, Vec3::new(-0.5, 0.6, -px), Vec3::new(0.5, 1.1, px)),
+    ];
+    let spin = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), std::f32::consts::PI * 4.0 * t);
+    instances.push(Instance::new(sign, Mat4::trs(Vec3::new(1.5, height(1, 8) as f32, 8.5), spin, 1.0)));
+
+    let (l, w, h) = (0.5, 0.35, 0.6);
+    let cart = vec![
+        board(metal, Vec3::new(-l, 0.1, -w), Vec3::new(l, 0.1 + px, w)),
+        board(metal, Vec3::new(-l, 0.1, -w), Vec3::new(-l + px, h, w)),
+        board(metal, Vec3::new(l - px, 0.1, -w), Vec3::new(l, h, w)),
+        board(metal, Vec3::new(-l, 0.1, -w), Vec3::new(l, h, -w + px)),
+        board(metal, Vec3::new(-l, 0.1, w - px), Vec3::new(l, h, w)),
+    ];
+    let yaw = Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 0.45);
+    let tilt = Quat::from_axis_angle(Vec3::new(1.0, 0.0, 0.0), -0.15);
+    instances.push(Instance::new(cart, Mat4::trs(Vec3::new(12.5, height(12, 8) as f32, 8.5), yaw.mul(tilt), 1.0)));
+
+    // el marco mira a +z en su espacio; girado 90° queda en la pared este de la casa
+    let frame = Mat4::trs(
+        Vec3::new(bx as f32 + 5.0, base_h + 2.5, bz as f32 + 2.5),
+        Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), std::f32::consts::FRAC_PI_2),
+        1.0,
+    );
+    instances.push(Instance::new(vec![board(planks, Vec3::new(-0.375, -0.375, 0.0), Vec3::new(0.375, 0.375, px))], frame));
+    let item = Mat4::trs(Vec3::new(0.0, 0.0, 0.2), Quat::from_axis_angle(Vec3::new(0.0, 0.0, 1.0), std::f32::consts::FRAC_PI_4), 0.3);
+    let cube = reg.aabb(metal, Vec3::new(-0.5, -0.5, -0.5), Vec3::new(0.5, 0.5, 0.5));
+    instances.push(Instance::new(vec![cube], frame.mul(item)));
+
+    // racimo de cristales de amatista al pie de la colina, en una sola malla
+    let amethyst = reg.surface(reg.id("amethyst_block"));
+    let ground = Vec3::new(5.5, height(5, 5) as f32 - 0.1, 5.5);
+    let mut tris = Vec::new();
+    for (lean, len, r) in [(Vec3::new(0.0, 1.0, 0.0), 1.2, 0.18), (Vec3::new(0.5, 1.0, 0.2), 0.8, 0.14), (Vec3::new(-0.3, 1.0, -0.4), 0.7, 0.12)] {
+        tris.extend(crystal(ground, lean.norm(), len, r, &amethyst));
+    }
+    let mut shapes = vec![Shape::Mesh(Mesh::new(tris))];
+
+    // farol de hierro importado de un .obj, junto a la valla
+    let lantern_at = Vec3::new(9.5, height(9, 8) as f32, 8.5);
+    match obj::load("assets/models/farol.obj") {
+        Ok(model) => {
+            let tris = model.triangles(&mut reg, "farol", Mat4::trs(lantern_at, Quat::from_axis_angle(Vec3::new(0.0, 1.0, 0.0), 0.4), 1.0), &[]);
+            shapes.push(Shape::Mesh(Mesh::new(tris)));
+        }
+        Err(e) => eprintln!("aviso: assets/models/farol.obj: {}", e),
+    }
 
     // Arboles de distintos tamaños 
     let trees = vec![
@@ -194,46 +275,71 @@ pub fn build_scene_minecraft(t: f32) -> (Scene, Anim) {
     ];
 
     for (tx, tz, trunk_h, levels, base_size) in trees {
-        let th = height(tx as i32, tz as i32) as f32;
+        let th = height(tx, tz) as f32;
 
         // tronco
         for y in 0..trunk_h {
-            cubes.push(Aabb {
-                min: Vec3::new(tx as f32 + 0.25, th + y as f32, tz as f32 + 0.25),
-                max: Vec3::new(tx as f32 + 0.75, th + y as f32 + 1.0, tz as f32 + 0.75),
-                mat_id: 3, face_tex: Some(one_tex(4)),
-            });
+            cubes.push(reg.aabb(
+                log,
+                Vec3::new(tx as f32 + 0.25, th + y as f32, tz as f32 + 0.25),
+                Vec3::new(tx as f32 + 0.75, th + y as f32 + 1.0, tz as f32 + 0.75),
+            ));
         }
 
         // copa de los arboles
         let crown_base_y = th + trunk_h as f32; 
         for i in 0..levels {
-            let level_size = (base_size as i32 - i as i32).max(0); 
+            let level_size = (base_size - i).max(0); 
             let y = crown_base_y + i as f32;
         
-            for z in (tz - level_size)..=(tz + level_size) {
-                for x in (tx - level_size)..=(tx + level_size) {
-                    push_block(&mut cubes, x as f32, y, z as f32, 4, one_tex(5)); 
-                }
-            }
+            let (lo, hi) = ([tx - level_size, y as i32, tz - level_size], [tx + level_size, y as i32, tz + level_size]);
+            world.fill(BlockBox::new(lo, hi), Some(leaves));
         }
     }
 
+    cubes.extend(world.cubes(&reg, Vec3::new(0.0, 0.0, 0.0)));
+
     // NOTA: Agregar skybox
     let skybox = CubeMap::from_folder("assets/skybox");
 
-    let scene = Scene { cubes, materials, textures, sun_dir, sun_col, sky_mix, skybox };
+    // luz cálida en la llama de cada antorcha
+    let mut lights: Vec<PointLight> = torch_at.iter().map(|&[x, y, z]| PointLight {
+        pos: Vec3::new(x as f32 + 0.5, y as f32 + 0.8, z as f32 + 0.6),
+        color: Color::new(1.0, 0.7, 0.35),
+        intensity: 1.5,
+    }).collect();
+    lights.push(PointLight { pos: lantern_at.add(Vec3::new(0.0, 0.9, 0.0)), color: Color::new(1.0, 0.75, 0.4), intensity: 1.2 });
+
+    let (textures, materials) = reg.into_parts();
+    let scene = Scene { cubes, materials, textures, sun_dir, sun_col, lights, sky_mix, skybox, time: 0.0, shutter: (0.0, 0.0), instances, shapes, bvh: Bvh::default() };
 
     // Cámara: una vuelta completa
-    let angle = std::f32::consts::PI * 2.0 * t;
-    let radius = 18.0 + 1.5 * (0.5 - (t * 2.0 * std::f32::consts::PI).cos() * 0.5);
-    let eye_h = 6.5;
-    let anim = Anim { angle, radius, eye_h, sky_mix };
+    let anim = Anim { radius: 18.0, wobble: 1.5, eye_h: 6.5, target: Vec3::new(8.0, 2.0, 8.0) };
 
     (scene, anim)
 }
 
+/// Paisaje generado con `terrain::generate` en vez del diorama hecho a mano.
+pub fn build_scene_terrain(t: f32, biome: Biome, params: &Params) -> (Scene, Anim) {
+    let (sun_dir, sun_col, sky_mix) = sun_cycle(t);
 
-pub fn build_scene_basic(t: f32) -> (Scene, Anim) {
-    build_scene_minecraft(t)
+    let mut reg = BlockRegistry::minecraft();
+    reg.biome = biome;
+    let mut world = terrain::generate(params, &reg).expect("el registro minecraft tiene todos los estratos");
+    structures::place(&mut world, &reg, &structures::Params::new(params.seed)).expect("el registro minecraft tiene los bloques de las estructuras");
+    vegetation::plant(&mut world, &reg, &vegetation::Params::for_biome(params.seed, biome)).expect("el registro minecraft tiene árboles");
+    let cubes = world.cubes(&reg, Vec3::new(0.0, 0.0, 0.0));
+
+    let skybox = CubeMap::from_folder("assets/skybox");
+    let (textures, materials) = reg.into_parts();
+    let scene = Scene { cubes, materials, textures, sun_dir, sun_col, lights: Vec::new(), sky_mix, skybox, time: 0.0, shutter: (0.0, 0.0), instances: Vec::new(), shapes: Vec::new(), bvh: Bvh::default() };
+
+    // órbita alrededor del centro, a una distancia proporcional al tamaño
+    let [sx, sz] = params.size;
+    let extent = sx.max(sz) as f32;
+    let target = Vec3::new(sx as f32 * 0.5, params.sea_level as f32, sz as f32 * 0.5);
+    let anim = Anim { radius: extent * 1.05, wobble: 0.0, eye_h: params.sea_level as f32 + extent * 0.55, target };
+
+    (scene, anim)
 }
+
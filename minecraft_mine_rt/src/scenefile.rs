@@ -0,0 +1,1054 @@
+//! Archivos de escena `.toml` (subconjunto escrito a mano): texturas,
+//! materiales, bloques, cajas, esferas, planos, triángulos, modelos `.vox` y `.obj`,
+//! estructuras `.nbt`/`.schem`, zonas de mundos guardados, paisajes generados,
+//! luces, sol, cielo, cámara y recorridos de cámara.
+//!
+//! ```toml
+//! registry = "minecraft"        # parte de los bloques del diorama
+//!
+//! [camera]
+//! eye = [24, 8, 20]
+//! target = [8, 2, 8]
+//!
+//! [[fill]]                      # bloques unitarios, esquinas inclusivas
+//! block = "grass"
+//! from = [0, 0, 0]
+//! to = [15, 0, 15]
+//!
+//! [[replace]]                   # también [[hollow]]; "air" vacía
+//! old = "grass"
+//! block = "dirt"
+//! from = [0, 0, 0]
+//! to = [3, 0, 3]
+//! time = 2.0                    # se aplica desde ese segundo de la animación
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::aabb::{Aabb, ALL_FACES, FACE_TOP};
+use crate::anvil;
+use crate::biome::{Biome, TintKind};
+use crate::block::{BlockId, BlockRegistry, Faces, Facing, Model, Shapes};
+use crate::bvh::Bvh;
+use crate::camera::{Camera, Lens, Projection, Section};
+use crate::color::Color;
+use crate::material::Material;
+use crate::instance::Instance;
+use crate::keyframes::{CameraPath, Ease, Interp, Key};
+use crate::math::{Mat4, Quat, Vec3};
+use crate::merge;
+use crate::obj;
+use crate::renderer::{PointLight, Scene};
+use crate::schematic;
+use crate::shape::{Mesh, Shape, Triangle};
+use crate::skybox::CubeMap;
+use crate::structures::{self, Kind};
+use crate::terrain::{self, Params};
+use crate::vegetation::{self, Species};
+use crate::texture::{TexKind, Texture};
+use crate::vox;
+use crate::world::{BlockBox, MAX_BLOCKS, World};
+
+/// Error de sintaxis o de contenido, con la línea donde ocurrió (0 = sin línea).
+#[derive(Debug)]
+pub struct SceneError {
+    pub line: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.line > 0 { write!(f, "línea {}: {}", self.line, self.msg) } else { write!(f, "{}", self.msg) }
+    }
+}
+
+impl std::error::Error for SceneError {}
+
+fn err<T>(line: usize, msg: impl Into<String>) -> Result<T, SceneError> {
+    Err(SceneError { line, msg: msg.into() })
+}
+
+/* =======================
+   Sintaxis
+   ======================= */
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Array(Vec<Value>),
+}
+
+pub struct Entry {
+    pub value: Value,
+    pub line: usize,
+}
+
+/// Tabla `[nombre]` o elemento de `[[nombre]]`; la raíz se llama "".
+pub struct Table {
+    pub name: String,
+    pub line: usize,
+    entries: Vec<(String, Entry)>,
+}
+
+/// Tipos que se pueden leer de un `Value`.
+pub trait FromValue: Sized {
+    const KIND: &'static str;
+    fn from_value(v: &Value) -> Option<Self>;
+}
+
+impl FromValue for String {
+    const KIND: &'static str = "una cadena";
+    fn from_value(v: &Value) -> Option<Self> {
+        if let Value::Str(s) = v { Some(s.clone()) } else { None }
+    }
+}
+
+impl FromValue for f32 {
+    const KIND: &'static str = "un número";
+    fn from_value(v: &Value) -> Option<Self> {
+        if let Value::Num(n) = v { Some(*n as f32) } else { None }
+    }
+}
+
+impl FromValue for i64 {
+    const KIND: &'static str = "un entero";
+    fn from_value(v: &Value) -> Option<Self> {
+        match v { Value::Num(n) if n.fract() == 0.0 => Some(*n as i64), _ => None }
+    }
+}
+
+impl FromValue for bool {
+    const KIND: &'static str = "true o false";
+    fn from_value(v: &Value) -> Option<Self> {
+        if let Value::Bool(b) = v { Some(*b) } else { None }
+    }
+}
+
+impl<T: FromValue> FromValue for Vec<T> {
+    const KIND: &'static str = "un arreglo";
+    fn from_value(v: &Value) -> Option<Self> {
+        if let Value::Array(items) = v { items.iter().map(T::from_value).collect() } else { None }
+    }
+}
+
+impl FromValue for Vec3 {
+    const KIND: &'static str = "un arreglo de 3 números";
+    fn from_value(v: &Value) -> Option<Self> {
+        match Vec::<f32>::from_value(v)?.as_slice() {
+            &[x, y, z] => Some(Vec3::new(x, y, z)),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Color {
+    const KIND: &'static str = "un color [r, g, b]";
+    fn from_value(v: &Value) -> Option<Self> {
+        let c = Vec3::from_value(v)?;
+        Some(Color::new(c.x, c.y, c.z))
+    }
+}
+
+impl Table {
+    fn new(name: &str, line: usize) -> Self {
+        Self { name: name.to_string(), line, entries: Vec::new() }
+    }
+
+    fn title(&self) -> String {
+        if self.name.is_empty() { "la raíz".to_string() } else { format!("[{}]", self.name) }
+    }
+
+    /// Valor opcional con la línea donde aparece; lo marca como usado.
+    pub fn get_at<T: FromValue>(&mut self, key: &str) -> Result<Option<(T, usize)>, SceneError> {
+        let Some(i) = self.entries.iter().position(|(k, _)| k == key) else { return Ok(None) };
+        let (_, e) = self.entries.remove(i);
+        match T::from_value(&e.value) {
+            Some(v) => Ok(Some((v, e.line))),
+            None => err(e.line, format!("'{}' debe ser {}", key, T::KIND)),
+        }
+    }
+
+    pub fn get<T: FromValue>(&mut self, key: &str) -> Result<Option<T>, SceneError> {
+        Ok(self.get_at(key)?.map(|(v, _)| v))
+    }
+
+    pub fn need_at<T: FromValue>(&mut self, key: &str) -> Result<(T, usize), SceneError> {
+        match self.get_at(key)? {
+            Some(v) => Ok(v),
+            None => err(self.line, format!("falta '{}' en {}", key, self.title())),
+        }
+    }
+
+    pub fn need<T: FromValue>(&mut self, key: &str) -> Result<T, SceneError> {
+        Ok(self.need_at(key)?.0)
+    }
+
+    pub fn or<T: FromValue>(&mut self, key: &str, default: T) -> Result<T, SceneError> {
+        Ok(self.get(key)?.unwrap_or(default))
+    }
+
+    /// Falla si quedó alguna clave sin leer (casi siempre un error de tipeo).
+    pub fn finish(self) -> Result<(), SceneError> {
+        match self.entries.first() {
+            Some((k, e)) => err(e.line, format!("clave desconocida '{}' en {}", k, self.title())),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Devuelve las tablas en orden de aparición; la primera es la raíz.
+pub fn parse(src: &str) -> Result<Vec<Table>, SceneError> {
+    let mut p = Parser { src: src.chars().collect(), pos: 0, line: 1 };
+    let mut tables = vec![Table::new("", 1)];
+    loop {
+        p.skip_blank();
+        let Some(c) = p.peek() else { break };
+        if c == '[' {
+            let line = p.line;
+            p.bump();
+            let array = p.eat('[');
+            let name = p.key()?;
+            if !p.eat(']') || (array && !p.eat(']')) {
+                return err(line, "encabezado de tabla sin cerrar");
+            }
+            p.end_of_line()?;
+            if !array && tables.iter().any(|t| t.name == name) {
+                return err(line, format!("tabla [{}] repetida", name));
+            }
+            tables.push(Table::new(&name, line));
+        } else {
+            let line = p.line;
+            let key = p.key()?;
+            p.skip_inline_ws();
+            if !p.eat('=') { return err(line, format!("se esperaba '=' después de '{}'", key)); }
+            p.skip_inline_ws();
+            let value = p.value()?;
+            p.end_of_line()?;
+            let table = tables.last_mut().unwrap();
+            if table.entries.iter().any(|(k, _)| *k == key) {
+                return err(line, format!("clave '{}' repetida", key));
+            }
+            table.entries.push((key, Entry { value, line }));
+        }
+    }
+    Ok(tables)
+}
+
+struct Parser {
+    src: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> { self.src.get(self.pos).copied() }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' { self.line += 1; }
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) { self.bump(); true } else { false }
+    }
+
+    fn skip_inline_ws(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\r')) { self.bump(); }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while self.peek().is_some_and(|c| c != '\n') { self.bump(); }
+        }
+    }
+
+    /// Espacios, saltos de línea y comentarios.
+    fn skip_blank(&mut self) {
+        loop {
+            self.skip_inline_ws();
+            self.skip_comment();
+            if !self.eat('\n') { break; }
+        }
+    }
+
+    fn end_of_line(&mut self) -> Result<(), SceneError> {
+        self.skip_inline_ws();
+        self.skip_comment();
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => { self.bump(); Ok(()) }
+            Some(c) => err(self.line, format!("texto inesperado '{}' al final de la línea", c)),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, SceneError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') { self.bump(); }
+        if self.pos == start {
+            return match self.peek() {
+                Some(c) => err(self.line, format!("se esperaba un nombre, encontré '{}'", c)),
+                None => err(self.line, "se esperaba un nombre"),
+            };
+        }
+        Ok(self.src[start..self.pos].iter().collect())
+    }
+
+    fn value(&mut self) -> Result<Value, SceneError> {
+        let line = self.line;
+        match self.peek() {
+            Some('"') => {
+                self.bump();
+                let mut s = String::new();
+                loop {
+                    match self.bump() {
+                        Some('"') => break,
+                        Some('\\') => match self.bump() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(c @ ('"' | '\\')) => s.push(c),
+                            _ => return err(line, "escape inválido en la cadena"),
+                        },
+                        Some('\n') | None => return err(line, "cadena sin cerrar"),
+                        Some(c) => s.push(c),
+                    }
+                }
+                Ok(Value::Str(s))
+            }
+            Some('[') => {
+                self.bump();
+                let mut items = Vec::new();
+                loop {
+                    self.skip_blank();
+                    if self.eat(']') { break; }
+                    items.push(self.value()?);
+                    self.skip_blank();
+                    if self.eat(',') { continue; }
+                    if self.eat(']') { break; }
+                    return match self.peek() {
+                        Some(_) => err(self.line, "se esperaba ',' o ']' en el arreglo"),
+                        None => err(line, "arreglo sin cerrar"),
+                    };
+                }
+                Ok(Value::Array(items))
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                let start = self.pos;
+                while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')) {
+                    self.bump();
+                }
+                let text: String = self.src[start..self.pos].iter().collect();
+                match text.parse::<f64>() {
+                    Ok(n) => Ok(Value::Num(n)),
+                    Err(_) => err(line, format!("número inválido '{}'", text)),
+                }
+            }
+            Some(c) if c.is_ascii_alphabetic() => match self.key()?.as_str() {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                w => err(line, format!("valor inesperado '{}' (¿faltan comillas?)", w)),
+            },
+            Some(c) => err(line, format!("valor inesperado '{}'", c)),
+            None => err(line, "falta el valor"),
+        }
+    }
+}
+
+/* =======================
+   Construcción de la escena
+   ======================= */
+
+pub struct SceneFile {
+    pub scene: Scene,
+    pub camera: Camera,
+    /// Recorrido de [[keyframe]]: mueve `camera` en cada `update`
+    pub path: Option<CameraPath>,
+    section: Option<Section>,
+    script: Script,
+}
+
+impl SceneFile {
+    /// Deja la escena lista para el cuadro en `time`: cortes, preproceso y BVH.
+    /// La geometría se prepara la primera vez y, si hay ediciones con tiempo, en cada cuadro.
+    pub fn update(&mut self, time: f32, cut: Option<Section>) -> Option<merge::Stats> {
+        self.scene.time = time;
+        if let Some(path) = &self.path { self.camera = path.camera(time, self.camera.aspect); }
+        let animated = !self.script.timed.is_empty();
+        if !self.scene.bvh.is_empty() && !animated {
+            // las cajas con velocidad cambian de lugar en cada cuadro
+            if self.scene.cubes.iter().any(|c| c.motion.is_some()) { self.scene.build_bvh(); }
+            return None;
+        }
+        if animated { self.scene.cubes = self.script.cubes(time); }
+        for c in [self.section, cut].into_iter().flatten() { c.apply(&mut self.scene.cubes, self.camera.view_eye()); }
+        let stats = self.scene.optimize();
+        self.scene.build_bvh();
+        Some(stats)
+    }
+}
+
+/// Ediciones de bloques de [[fill]], [[replace]] y [[hollow]]; `None` es aire.
+enum Edit {
+    Fill(Option<BlockId>),
+    /// Bloque buscado y bloque nuevo
+    Replace(Option<BlockId>, Option<BlockId>),
+    Hollow(Option<BlockId>),
+}
+
+impl Edit {
+    fn apply(&self, world: &mut World, b: BlockBox) {
+        match *self {
+            Edit::Fill(block) => world.fill(b, block),
+            Edit::Replace(from, to) => { world.replace(b, from, to); }
+            Edit::Hollow(block) => world.hollow(b, block),
+        }
+    }
+}
+
+/// Geometría de la escena: la fija y una rejilla con las ediciones aplicadas en
+/// orden. Las del principio que no esperan se aplican una vez al cargar; desde la
+/// primera con `time` (segundos) > 0 se reaplican en cada cuadro las que ya llegaron.
+struct Script {
+    /// Rejilla que abarca todas las ediciones, con las iniciales ya aplicadas
+    base: Option<World>,
+    timed: Vec<(BlockBox, Edit, f32)>,
+    /// Copia de `base` para las ediciones con tiempo, reutilizada entre cuadros
+    scratch: Option<World>,
+    /// Geometría de cada bloque, porque el registro pasa a la escena
+    shapes: Shapes,
+    fixed: Vec<Aabb>,
+}
+
+impl Script {
+    /// `bounds` abarca todas las `edits` y ya está acotada a `MAX_BLOCKS`.
+    fn new(mut edits: Vec<(BlockBox, Edit, f32)>, bounds: Option<BlockBox>, shapes: Shapes, fixed: Vec<Aabb>) -> Self {
+        let timed = edits.split_off(edits.iter().position(|e| e.2 > 0.0).unwrap_or(edits.len()));
+        let base = bounds.map(|bounds| {
+            let mut world = World::covering(bounds).expect("la caja de las ediciones se acota al cargar");
+            for (b, edit, _) in &edits { edit.apply(&mut world, *b); }
+            world
+        });
+        Self { base, timed, scratch: None, shapes, fixed }
+    }
+
+    fn cubes(&mut self, time: f32) -> Vec<Aabb> {
+        let mut cubes = self.fixed.clone();
+        let Some(base) = &self.base else { return cubes };
+        let world = if self.timed.is_empty() {
+            base
+        } else {
+            let world = self.scratch.get_or_insert_with(|| base.clone());
+            world.copy_from(base);
+            for (b, edit, t) in &self.timed {
+                if *t <= time { edit.apply(world, *b); }
+            }
+            world
+        };
+        cubes.extend(world.compile(&self.shapes, Vec3::new(0.0, 0.0, 0.0)));
+        cubes
+    }
+}
+
+pub fn load(path: &str, aspect: f32) -> Result<SceneFile, SceneError> {
+    let src = fs::read_to_string(path).map_err(|e| SceneError { line: 0, msg: format!("no pude leer {}: {}", path, e) })?;
+    build(parse(&src)?, aspect)
+}
+
+/// Solo un recorrido de cámara, para las escenas que no vienen de archivo.
+pub fn load_path(path: &str, aspect: f32) -> Result<CameraPath, SceneError> {
+    let src = fs::read_to_string(path).map_err(|e| SceneError { line: 0, msg: format!("no pude leer {}: {}", path, e) })?;
+    let mut tables = parse(&src)?.into_iter();
+    tables.next().unwrap().finish()?;
+    let tables: Vec<Table> = tables.collect();
+    if let Some(t) = tables.iter().find(|t| !matches!(t.name.as_str(), "camera" | "keyframe")) {
+        return err(t.line, format!("un recorrido solo lleva [camera] y [[keyframe]], no [{}]", t.name));
+    }
+    views_of(tables, aspect)?.path.ok_or_else(|| SceneError { line: 0, msg: "el recorrido no tiene ninguna [[keyframe]]".to_string() })
+}
+
+pub fn build(tables: Vec<Table>, aspect: f32) -> Result<SceneFile, SceneError> {
+    // Las definiciones van antes que la geometría sin importar el orden del archivo
+    let mut tables = tables.into_iter();
+    let mut root = tables.next().unwrap();
+    let (mut defs, rest): (Vec<Table>, Vec<Table>) =
+        tables.partition(|t| matches!(t.name.as_str(), "texture" | "material" | "block"));
+    let (views, mut rest): (Vec<Table>, Vec<Table>) = rest.into_iter().partition(|t| matches!(t.name.as_str(), "camera" | "keyframe"));
+    defs.sort_by_key(|t| match t.name.as_str() { "texture" => 0, "material" => 1, _ => 2 });
+
+    let mut reg = match root.get_at::<String>("registry")? {
+        None => BlockRegistry::new(),
+        Some((r, _)) if r == "minecraft" => BlockRegistry::minecraft(),
+        Some((r, line)) => return err(line, format!("registro desconocido '{}'", r)),
+    };
+    if let Some((b, line)) = root.get_at::<String>("biome")? {
+        reg.biome = Biome::by_name(&b).ok_or_else(|| SceneError { line, msg: format!("bioma desconocido '{}'", b) })?;
+    }
+    root.finish()?;
+
+    for t in defs {
+        match t.name.as_str() {
+            "texture" => texture(&mut reg, t)?,
+            "material" => material(&mut reg, t)?,
+            _ => block(&mut reg, t)?,
+        }
+    }
+
+    let mut cubes = Vec::new();
+    let mut instances = Vec::new();
+    let mut shapes = Vec::new();
+    let mut lights = Vec::new();
+    let mut sun_dir = Vec3::new(0.3, -1.0, 0.2).norm();
+    let mut sun_col = Color::new(1.0, 0.95, 0.85);
+    let mut sky_mix = 0.0;
+    let mut skybox = None;
+    let Views { camera, path, section } = views_of(views, aspect)?;
+    let (mut edits, mut bounds) = (Vec::new(), None::<BlockBox>);
+
+    for mut t in rest.drain(..) {
+        match t.name.as_str() {
+            "sun" => {
+                let (dir, line) = t.get_at::<Vec3>("dir")?.unwrap_or((sun_dir, t.line));
+                if dir.len() < 1e-6 { return err(line, "dir no puede ser [0, 0, 0]"); }
+                sun_dir = dir.norm();
+                sun_col = t.or("color", sun_col)?;
+            }
+            "sky" => {
+                sky_mix = t.or("mix", 0.0)?;
+                if let Some((dir, line)) = t.get_at::<String>("skybox")? {
+                    skybox = Some(CubeMap::from_folder(&dir).ok_or_else(|| SceneError {
+                        line,
+                        msg: format!("no pude cargar el skybox de {}", dir),
+                    })?);
+                }
+            }
+            "light" => {
+                let pos = t.need("pos")?;
+                let color = t.or("color", Color::splat(1.0))?;
+                let intensity = t.or("intensity", 1.0)?;
+                lights.push(PointLight { pos, color, intensity });
+            }
+            "fill" | "replace" | "hollow" => {
+                let block = block_or_air(&reg, &mut t, "block")?;
+                let (a, b): (Vec3, Vec3) = (t.need("from")?, t.need("to")?);
+                let ints = |v: Vec3| [v.x.floor() as i32, v.y.floor() as i32, v.z.floor() as i32];
+                let edit = match t.name.as_str() {
+                    "fill" => Edit::Fill(block),
+                    "hollow" => Edit::Hollow(block),
+                    _ => Edit::Replace(block_or_air(&reg, &mut t, "old")?, block),
+                };
+                let b = BlockBox::new(ints(a), ints(b));
+                // todas las ediciones comparten una rejilla que las abarca
+                let all = bounds.map_or(b, |u| u.union(b));
+                if all.volume().is_none() {
+                    return err(t.line, format!("las ediciones abarcan más de {} bloques", MAX_BLOCKS));
+                }
+                bounds = Some(all);
+                edits.push((b, edit, t.or("time", 0.0)?));
+            }
+            "box" => {
+                let id = block_ref(&reg, &mut t)?;
+                let (a, b): (Vec3, Vec3) = (t.need("min")?, t.need("max")?);
+                let mut c = reg.aabb(id, min3(a, b), max3(a, b));
+                c.motion = t.get("velocity")?;
+                cubes.push(c);
+            }
+            "sphere" => {
+                let surface = reg.surface(block_ref(&reg, &mut t)?);
+                let (radius, line) = t.need_at::<f32>("radius")?;
+                if radius <= 0.0 { return err(line, "radius debe ser mayor que 0"); }
+                shapes.push(Shape::Sphere { center: t.need("center")?, radius, surface });
+            }
+            "plane" => {
+                let surface = reg.surface(block_ref(&reg, &mut t)?);
+                let (normal, line) = t.get_at::<Vec3>("normal")?.unwrap_or((Vec3::new(0.0, 1.0, 0.0), t.line));
+                if normal.len() < 1e-6 { return err(line, "normal no puede ser [0, 0, 0]"); }
+                shapes.push(Shape::Plane { point: t.or("point", Vec3::new(0.0, 0.0, 0.0))?, normal: normal.norm(), surface });
+            }
+            "triangle" => {
+                let surface = reg.surface(block_ref(&reg, &mut t)?);
+                let p = [t.need("a")?, t.need("b")?, t.need("c")?];
+                shapes.push(Shape::Triangle(Triangle { p, n: None, uv: [(0.0, 0.0), (1.0, 0.0), (0.5, 1.0)], surface }));
+            }
+            "vox" => {
+                let (file, line) = t.need_at::<String>("file")?;
+                let at = t.or("at", Vec3::new(0.0, 0.0, 0.0))?;
+                let model = vox::load(&file).map_err(|e| SceneError { line, msg: format!("{}: {}", file, e) })?;
+                let name = Path::new(&file).file_stem().map_or(file.clone(), |s| s.to_string_lossy().into_owned());
+                transformed(&mut t, model.place(&mut reg, &name, at), &mut cubes, &mut instances)?;
+            }
+            "obj" => {
+                let (file, line) = t.need_at::<String>("file")?;
+                let model = obj::load(&file).map_err(|e| SceneError { line, msg: format!("{}: {}", file, e) })?;
+                let name = Path::new(&file).file_stem().map_or(file.clone(), |s| s.to_string_lossy().into_owned());
+                let (scale, sline) = t.get_at::<f32>("scale")?.unwrap_or((1.0, line));
+                if scale <= 0.0 { return err(sline, "scale debe ser mayor que 0"); }
+                let rotate = t.or("rotate", Vec3::new(0.0, 0.0, 0.0))?.mul(std::f32::consts::PI / 180.0);
+                let to_world = Mat4::trs(t.or("at", Vec3::new(0.0, 0.0, 0.0))?, Quat::from_euler(rotate), scale);
+                let tris = model.triangles(&mut reg, &name, to_world, &t.or("groups", Vec::new())?);
+                if tris.is_empty() { return err(line, format!("{}: no hay caras que dibujar", file)); }
+                shapes.push(Shape::Mesh(Mesh::new(tris)));
+            }
+            "schematic" => {
+                let (file, line) = t.need_at::<String>("file")?;
+                let at = t.or("at", Vec3::new(0.0, 0.0, 0.0))?;
+                let (rotation, rline) = t.get_at::<f32>("rotation")?.unwrap_or((0.0, line));
+                if rotation % 90.0 != 0.0 { return err(rline, "rotation debe ser 0, 90, 180 o 270"); }
+                let model = schematic::load(&file).map_err(|e| SceneError { line, msg: format!("{}: {}", file, e) })?;
+                let mirror = t.or("mirror", false)?;
+                transformed(&mut t, model.place(&reg, at, rotation as i32, mirror), &mut cubes, &mut instances)?;
+            }
+            "terrain" => {
+                let d = Params::default();
+                let (size, line) = t.get_at::<Vec<i64>>("size")?.unwrap_or((vec![d.size[0] as i64, d.size[1] as i64], t.line));
+                if size.len() != 2 || size.iter().any(|&n| !(1..=1024).contains(&n)) {
+                    return err(line, "size debe ser [ancho, fondo] entre 1 y 1024");
+                }
+                let p = Params {
+                    seed: t.or("seed", d.seed as i64)? as u64,
+                    size: [size[0] as i32, size[1] as i32],
+                    height: t.or("height", d.height as i64)?.clamp(4, 512) as i32,
+                    octaves: t.or("octaves", d.octaves as i64)?.clamp(1, 12) as u32,
+                    scale: t.or("scale", d.scale)?.max(1.0),
+                    amplitude: t.or("amplitude", d.amplitude)?,
+                    sea_level: t.or("sea_level", d.sea_level as i64)? as i32,
+                    overhang: t.or("overhang", d.overhang)?.max(0.0),
+                    caves: t.or("caves", d.caves)?,
+                    ores: t.or("ores", d.ores)?,
+                    lava_level: t.or("lava_level", d.lava_level as i64)? as i32,
+                };
+                // vegetación: por defecto la del bioma del registro
+                let v = vegetation::Params::for_biome(p.seed, reg.biome);
+                let species = match t.get_at::<Vec<String>>("species")? {
+                    Some((names, line)) => names.iter()
+                        .map(|n| Species::by_name(n).ok_or_else(|| SceneError { line, msg: format!("especie desconocida '{}'", n) }))
+                        .collect::<Result<_, _>>()?,
+                    None => v.species,
+                };
+                let v = vegetation::Params {
+                    seed: p.seed,
+                    spacing: t.or("tree_spacing", v.spacing)?.max(1.0),
+                    species: if t.or("trees", true)? { species } else { Vec::new() },
+                    grass: t.or("grass", v.grass)?.clamp(0.0, 1.0),
+                    flowers: t.or("flowers", v.flowers)?.clamp(0.0, 1.0),
+                };
+                // estructuras: todas por defecto, más las casas importadas para las aldeas
+                let mut sp = structures::Params::new(p.seed);
+                if let Some((names, line)) = t.get_at::<Vec<String>>("structures")? {
+                    sp.kinds = names.iter()
+                        .map(|n| Kind::by_name(n).ok_or_else(|| SceneError { line, msg: format!("estructura desconocida '{}'", n) }))
+                        .collect::<Result<_, _>>()?;
+                }
+                if let Some((files, line)) = t.get_at::<Vec<String>>("houses")? {
+                    for file in files {
+                        sp.houses.push(schematic::load(&file).map_err(|e| SceneError { line, msg: format!("{}: {}", file, e) })?);
+                    }
+                }
+                let at = t.or("at", Vec3::new(0.0, 0.0, 0.0))?;
+                let mut world = terrain::generate(&p, &reg).map_err(|msg| SceneError { line: t.line, msg })?;
+                structures::place(&mut world, &reg, &sp).map_err(|msg| SceneError { line: t.line, msg })?;
+                vegetation::plant(&mut world, &reg, &v).map_err(|msg| SceneError { line: t.line, msg })?;
+                cubes.extend(world.cubes(&reg, at));
+            }
+            "region" => {
+                // carpeta `region` de un mundo guardado y caja en coordenadas de bloque
+                let (dir, line) = t.need_at::<String>("dir")?;
+                let (a, b): (Vec3, Vec3) = (t.need("from")?, t.need("to")?);
+                let at = t.or("at", min3(a, b))?;
+                let ints = |v: Vec3| [v.x.floor() as i32, v.y.floor() as i32, v.z.floor() as i32];
+                let area = anvil::extract(&dir, ints(a), ints(b)).map_err(|e| SceneError { line, msg: format!("{}: {}", dir, e) })?;
+                cubes.extend(area.place(&reg, at, 0, false));
+            }
+            other => return err(t.line, format!("tabla desconocida [{}]", other)),
+        }
+        t.finish()?;
+    }
+
+    let mut script = Script::new(edits, bounds, reg.shapes(), cubes);
+    let cubes = script.cubes(0.0);
+
+    // Sin [camera]: mira el centro de la escena desde una esquina
+    let camera = camera.unwrap_or_else(|| {
+        let (lo, hi) = cubes.iter().fold((Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 0.0)), |(lo, hi), c| {
+            (min3(lo, c.min), max3(hi, c.max))
+        });
+        let center = lo.add(hi).mul(0.5);
+        let r = hi.sub(lo).len().max(4.0);
+        let eye = center.add(Vec3::new(0.8 * r, 0.45 * r, 0.6 * r));
+        Camera::new(eye, center, Vec3::new(0.0, 1.0, 0.0), 60.0, aspect)
+    });
+
+    let (textures, materials) = reg.into_parts();
+    let scene = Scene { cubes, materials, textures, sun_dir, sun_col, lights, sky_mix, skybox, time: 0.0, shutter: (0.0, 0.0), instances, shapes, bvh: Bvh::default() };
+    Ok(SceneFile { scene, camera, path, section, script })
+}
+
+/// Cámara fija de [camera] o recorrido de [[keyframe]], y el corte transversal.
+struct Views {
+    camera: Option<Camera>,
+    path: Option<CameraPath>,
+    section: Option<Section>,
+}
+
+/// Campo de visión en grados, estrictamente entre 0 y 180.
+fn fov_of(t: &mut Table) -> Result<Option<f32>, SceneError> {
+    match t.get_at::<f32>("fov")? {
+        Some((f, line)) if !(f > 0.0 && f < 180.0) => err(line, "fov debe estar entre 0 y 180 grados"),
+        v => Ok(v.map(|(f, _)| f)),
+    }
+}
+
+fn ease(t: &mut Table) -> Result<Option<Ease>, SceneError> {
+    let Some((name, line)) = t.get_at::<String>("ease")? else { return Ok(None) };
+    match Ease::from_name(&name) {
+        Some(e) => Ok(Some(e)),
+        None => err(line, format!("aceleración desconocida '{}' (linear, in, out o in-out)", name)),
+    }
+}
+
+/// Las claves sin `target`, `fov` o `roll` conservan los de la clave anterior (la
+/// primera, los de [camera]); con claves, [camera] solo aporta esos valores y `path`.
+fn views_of(tables: Vec<Table>, aspect: f32) -> Result<Views, SceneError> {
+    let (keyframes, camera): (Vec<Table>, Vec<Table>) = tables.into_iter().partition(|t| t.name == "keyframe");
+    let mut views = Views { camera: None, path: None, section: None };
+    let (mut target, mut up, mut fov, mut interp, mut default_ease) = (None, Vec3::new(0.0, 1.0, 0.0), 60.0, Interp::CatmullRom, Ease::Linear);
+    let (mut lens, mut projection) = (Lens::default(), Projection::default());
+    for mut t in camera {
+        target = t.get("target")?;
+        up = t.or("up", up)?;
+        fov = fov_of(&mut t)?.unwrap_or(fov);
+        lens.aperture = t.or("aperture", lens.aperture)?.max(0.0);
+        lens.focus = t.get::<f32>("focus")?.or(lens.focus);
+        if let Some((blades, line)) = t.get_at::<i64>("blades")? {
+            if blades != 0 && !(3..=32).contains(&blades) { return err(line, "'blades' va de 3 a 32 (0 = apertura circular)"); }
+            lens.blades = blades as u32;
+        }
+        if let Some((shift, line)) = t.get_at::<Vec<f32>>("shift")? {
+            let &[x, y] = shift.as_slice() else { return err(line, "'shift' es [x, y] en fracciones del alto de la imagen") };
+            lens.shift = (x, y);
+        }
+        lens.tilt = t.or("tilt", lens.tilt)?;
+        let height = t.get_at::<f32>("height")?;
+        if let Some((h, line)) = height && h <= 0.0 { return err(line, "height debe ser mayor que 0"); }
+        let height = height.map(|(h, _)| h);
+        if let Some((name, line)) = t.get_at::<String>("projection")? {
+            projection = Projection::by_name(&name, height)
+                .ok_or_else(|| SceneError { line, msg: format!("proyección desconocida '{}' (perspective, orthographic, isometric o dimetric)", name) })?;
+        } else if height.is_some() {
+            projection = Projection::Orthographic { height };
+        }
+        if keyframes.is_empty() {
+            let eye = t.need("eye")?;
+            let target = target.ok_or_else(|| SceneError { line: t.line, msg: "falta 'target' en [camera]".to_string() })?;
+            let mut cam = Camera::new(eye, target, up, fov, aspect);
+            (cam.lens, cam.projection) = (lens, projection);
+            views.camera = Some(cam);
+        }
+        if let Some((name, line)) = t.get_at::<String>("path")? {
+            interp = Interp::from_name(&name)
+                .ok_or_else(|| SceneError { line, msg: format!("recorrido desconocido '{}' (linear, catmull-rom o bezier)", name) })?;
+        }
+        default_ease = ease(&mut t)?.unwrap_or(default_ease);
+        if let Some((cut, line)) = t.get_at::<String>("section")? {
+            views.section = Some(Section::parse(&cut).ok_or_else(|| SceneError {
+                line,
+                msg: format!("corte inválido '{}' (se espera x=N, y=N o z=N)", cut),
+            })?);
+        }
+        t.finish()?;
+    }
+    if keyframes.is_empty() { return Ok(views); }
+
+    // cada clave con lo que trae escrito de target, fov y roll
+    let mut keys = Vec::with_capacity(keyframes.len());
+    for mut t in keyframes {
+        let (time, line) = t.need_at::<f32>("time")?;
+        let eye = t.need("eye")?;
+        let given = (t.get::<Vec3>("target")?, fov_of(&mut t)?, t.get::<f32>("roll")?);
+        let key_ease = ease(&mut t)?.unwrap_or(default_ease);
+        let (eye_handle, target_handle) = (t.get("eye_handle")?, t.get("target_handle")?);
+        t.finish()?;
+        keys.push((line, Key { time, eye, target: eye, fov, roll: 0.0, ease: key_ease, eye_handle, target_handle }, given));
+    }
+    keys.sort_by(|a, b| a.1.time.total_cmp(&b.1.time));
+    if let Some(w) = keys.windows(2).find(|w| w[0].1.time == w[1].1.time) {
+        return err(w[1].0, format!("dos claves en el segundo {}", w[1].1.time));
+    }
+    let mut roll = 0.0;
+    let keys = keys.into_iter().map(|(line, mut k, (t, f, r))| {
+        target = t.or(target);
+        k.target = target.ok_or_else(|| SceneError { line, msg: "la primera clave necesita 'target' (o uno en [camera])".to_string() })?;
+        (fov, roll) = (f.unwrap_or(fov), r.unwrap_or(roll));
+        (k.fov, k.roll) = (fov, roll);
+        Ok(k)
+    }).collect::<Result<Vec<_>, SceneError>>()?;
+
+    let mut path = CameraPath::new(keys, interp, up);
+    (path.lens, path.projection) = (lens, projection);
+    views.camera = Some(path.camera(0.0, aspect));
+    views.path = Some(path);
+    Ok(views)
+}
+
+fn min3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)) }
+fn max3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)) }
+
+/// Con `rotate` (grados alrededor de x, y y z, en ese orden) o `scale`, las cajas
+/// pasan a una instancia que gira y escala alrededor de su centro; si no, van tal cual.
+fn transformed(t: &mut Table, placed: Vec<Aabb>, cubes: &mut Vec<Aabb>, instances: &mut Vec<Instance>) -> Result<(), SceneError> {
+    let rotate = t.get::<Vec3>("rotate")?;
+    let scale = t.get_at::<f32>("scale")?;
+    if let Some((s, line)) = scale && s <= 0.0 { return err(line, "scale debe ser mayor que 0"); }
+    if (rotate.is_none() && scale.is_none()) || placed.is_empty() {
+        cubes.extend(placed);
+        return Ok(());
+    }
+    let (lo, hi) = placed.iter().fold((placed[0].min, placed[0].max), |(a, b), c| (min3(a, c.min), max3(b, c.max)));
+    let center = lo.add(hi).mul(0.5);
+    let q = Quat::from_euler(rotate.unwrap_or_default().mul(std::f32::consts::PI / 180.0));
+    let local = placed.into_iter().map(|mut c| {
+        (c.min, c.max) = (c.min.sub(center), c.max.sub(center));
+        c
+    }).collect();
+    instances.push(Instance::new(local, Mat4::trs(center, q, scale.map_or(1.0, |s| s.0))));
+    Ok(())
+}
+
+fn block_ref(reg: &BlockRegistry, t: &mut Table) -> Result<usize, SceneError> {
+    let (name, line) = t.need_at::<String>("block")?;
+    reg.find(&name).ok_or_else(|| SceneError { line, msg: format!("bloque desconocido '{}'", name) })
+}
+
+/// Bloque del registro o "air".
+fn block_or_air(reg: &BlockRegistry, t: &mut Table, key: &str) -> Result<Option<BlockId>, SceneError> {
+    let (name, line) = t.need_at::<String>(key)?;
+    if name == "air" { return Ok(None); }
+    reg.find(&name).map(Some).ok_or_else(|| SceneError { line, msg: format!("bloque desconocido '{}'", name) })
+}
+
+fn tex_ref(reg: &BlockRegistry, name: &str, line: usize) -> Result<usize, SceneError> {
+    reg.find_tex(name).ok_or_else(|| SceneError { line, msg: format!("textura desconocida '{}'", name) })
+}
+
+fn texture(reg: &mut BlockRegistry, mut t: Table) -> Result<(), SceneError> {
+    let name: String = t.need("name")?;
+    let fallback = match t.get_at::<String>("fallback")? {
+        Some((k, line)) => {
+            Some(TexKind::from_name(&k).ok_or_else(|| SceneError { line, msg: format!("textura procedural desconocida '{}'", k) })?)
+        }
+        None => None,
+    };
+    let frame_time: Option<f32> = t.get("frame_time")?;
+    let mut tex = match t.get_at::<String>("file")? {
+        Some((file, line)) => {
+            let loaded = match frame_time {
+                Some(ft) => Texture::from_ppm_strip(&file, ft),
+                None => Texture::from_file(&file),
+            };
+            match (loaded, fallback) {
+                (Some(tex), _) => tex,
+                (None, Some(kind)) => {
+                    eprintln!("aviso: textura '{}' no encontrada en {}, usando procedural", name, file);
+                    Texture::new(kind)
+                }
+                (None, None) => return err(line, format!("no pude cargar {}", file)),
+            }
+        }
+        None => match fallback {
+            Some(kind) => Texture::new(kind),
+            None => return err(t.line, format!("la textura '{}' necesita 'file' o 'fallback'", name)),
+        },
+    };
+    if let Some((flow, line)) = t.get_at::<Vec<f32>>("flow")? {
+        let &[su, sv, warp] = flow.as_slice() else { return err(line, "'flow' es [vel_u, vel_v, ondulación]") };
+        tex = Texture::flow(tex, (su, sv), warp);
+    }
+    if let Some(gain) = t.get("gray")? {
+        tex = Texture::grayscale(tex, gain);
+    }
+    reg.add_texture(&name, tex);
+    t.finish()
+}
+
+fn material(reg: &mut BlockRegistry, mut t: Table) -> Result<(), SceneError> {
+    let name: String = t.need("name")?;
+    let (tex, line) = t.need_at::<String>("texture")?;
+    let m = Material {
+        tex_id: tex_ref(reg, &tex, line)?,
+        albedo: t.or("albedo", 1.0)?,
+        specular: t.or("specular", 0.0)?,
+        transparency: t.or("transparency", 0.0)?,
+        reflectivity: t.or("reflectivity", 0.0)?,
+        ior: t.or("ior", 1.0)?,
+        shininess: t.or("shininess", 16.0)?,
+        emissive: t.or("emissive", Color::black())?,
+    };
+    reg.material(&name, m);
+    t.finish()
+}
+
+fn block(reg: &mut BlockRegistry, mut t: Table) -> Result<(), SceneError> {
+    let name: String = t.need("name")?;
+    let (mat, line) = t.need_at::<String>("material")?;
+    if reg.find_mat(&mat).is_none() {
+        return err(line, format!("material desconocido '{}'", mat));
+    }
+
+    // una de: texture | top/side/bottom | faces
+    let all = t.get_at::<String>("texture")?;
+    let column = [t.get_at::<String>("top")?, t.get_at::<String>("side")?, t.get_at::<String>("bottom")?];
+    let each = t.get_at::<Vec<String>>("faces")?;
+    let mut names: Vec<(&str, usize)> = Vec::new();
+    let faces = match (&all, &column, &each) {
+        (Some((a, l)), [None, None, None], None) => { names.push((a, *l)); Faces::All(a) }
+        (None, [Some((top, lt)), Some((side, ls)), Some((bottom, lb))], None) => {
+            names.extend([(top.as_str(), *lt), (side.as_str(), *ls), (bottom.as_str(), *lb)]);
+            Faces::Column { top, side, bottom }
+        }
+        (None, [None, None, None], Some((f, l))) => {
+            let Ok(f) = <[&str; 6]>::try_from(f.iter().map(String::as_str).collect::<Vec<_>>()) else {
+                return err(*l, "'faces' necesita 6 texturas: -X, +X, -Y, +Y, -Z, +Z");
+            };
+            names.extend(f.iter().map(|n| (*n, *l)));
+            Faces::Each(f)
+        }
+        _ => return err(t.line, format!("el bloque '{}' necesita 'texture', 'top'/'side'/'bottom' o 'faces'", name)),
+    };
+    for (n, l) in names {
+        tex_ref(reg, n, l)?;
+    }
+    let id = reg.block(&name, &mat, faces);
+
+    if let Some((kind, line)) = t.get_at::<String>("tint")? {
+        let kind = match kind.as_str() {
+            "grass" => TintKind::Grass,
+            "foliage" => TintKind::Foliage,
+            "spruce" => TintKind::Spruce,
+            "birch" => TintKind::Birch,
+            _ => return err(line, format!("tinte desconocido '{}' (grass, foliage, spruce o birch)", kind)),
+        };
+        let faces = match t.get_at::<String>("tint_faces")? {
+            None if matches!(kind, TintKind::Grass) => FACE_TOP,
+            None => ALL_FACES,
+            Some((f, _)) if f == "top" => FACE_TOP,
+            Some((f, _)) if f == "all" => ALL_FACES,
+            Some((f, line)) => return err(line, format!("'tint_faces' debe ser \"top\" o \"all\", no '{}'", f)),
+        };
+        reg.tinted(id, kind, faces);
+    }
+    match t.get_at::<String>("model")? {
+        None => {}
+        Some((m, _)) if m == "cube" => {}
+        Some((m, line)) => {
+            let model = match m.as_str() {
+                "cross" => Model::Cross,
+                "slab" => Model::Slab { top: false },
+                "stairs" => Model::Stairs { facing: Facing::North, top: false },
+                "fence" => Model::Fence,
+                "pane" => Model::Pane,
+                "torch" => Model::Torch { wall: None },
+                "wall_torch" => Model::Torch { wall: Some(Facing::North) },
+                _ => return err(line, format!("modelo desconocido '{}' (cube, cross, slab, stairs, fence, pane, torch o wall_torch)", m)),
+            };
+            reg.model(id, model);
+        }
+    }
+    t.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Línea y mensaje del error de `parse`.
+    fn parse_err(src: &str) -> (usize, String) {
+        let e = parse(src).err().expect("debía fallar");
+        (e.line, e.msg)
+    }
+
+    /// Error de una escena completa (sin registro, no carga texturas).
+    fn build_err(src: &str) -> (usize, String) {
+        let e = parse(src).and_then(|t| build(t, 1.0)).err().expect("debía fallar");
+        (e.line, e.msg)
+    }
+
+    #[test]
+    fn parses_tables_and_values() {
+        let src = "\
+# comentario
+name = \"a \\\"b\\\"\\n\"  # al final de la línea
+[sun]
+dir = [1, -2.5, +3e1]
+[[light]]
+on = true
+list = [
+  [1, 2],   # anidados y en varias líneas
+  [],
+]
+[[light]]
+pos = [0, 0, 0]
+";
+        let mut tables = parse(src).unwrap();
+        let names: Vec<(&str, usize)> = tables.iter().map(|t| (t.name.as_str(), t.line)).collect();
+        assert_eq!(names, [("", 1), ("sun", 3), ("light", 5), ("light", 11)]);
+        assert_eq!(tables[0].get::<String>("name").unwrap().as_deref(), Some("a \"b\"\n"));
+        let dir: Vec3 = tables[1].need("dir").unwrap();
+        assert_eq!((dir.x, dir.y, dir.z), (1.0, -2.5, 30.0));
+        assert_eq!(tables[2].get_at::<bool>("on").unwrap(), Some((true, 6)));
+        let list: Vec<Vec<i64>> = tables[2].need("list").unwrap();
+        assert_eq!(list, [vec![1, 2], vec![]]);
+        assert!(tables[3].need::<Vec3>("pos").is_ok());
+        for t in tables { t.finish().unwrap(); }
+    }
+
+    #[test]
+    fn syntax_errors_have_their_line() {
+        assert_eq!(parse_err("a = 1\n[sun\n"), (2, "encabezado de tabla sin cerrar".to_string()));
+        assert_eq!(parse_err("\n\nb 1"), (3, "se esperaba '=' después de 'b'".to_string()));
+        assert_eq!(parse_err("s = \"abc\nt = 1"), (1, "cadena sin cerrar".to_string()));
+        assert_eq!(parse_err("a = [1, 2\n\n"), (1, "arreglo sin cerrar".to_string()));
+        assert_eq!(parse_err("a = [1 2]"), (1, "se esperaba ',' o ']' en el arreglo".to_string()));
+        assert_eq!(parse_err("a = 1 2"), (1, "texto inesperado '2' al final de la línea".to_string()));
+        assert_eq!(parse_err("a = 1x"), (1, "número inválido '1x'".to_string()));
+        assert_eq!(parse_err("a = yes"), (1, "valor inesperado 'yes' (¿faltan comillas?)".to_string()));
+        assert_eq!(parse_err("[sun]\n[sun]"), (2, "tabla [sun] repetida".to_string()));
+        assert_eq!(parse_err("a = 1\na = 2"), (2, "clave 'a' repetida".to_string()));
+    }
+
+    #[test]
+    fn error_display_shows_line() {
+        assert_eq!(SceneError { line: 4, msg: "x".into() }.to_string(), "línea 4: x");
+        assert_eq!(SceneError { line: 0, msg: "x".into() }.to_string(), "x");
+    }
+
+    #[test]
+    fn unknown_and_mistyped_keys() {
+        let mut tables = parse("[sun]\ndir = [0, -1, 0]\ncolour = [1, 1, 1]\n").unwrap();
+        let mut sun = tables.remove(1);
+        sun.need::<Vec3>("dir").unwrap();
+        let e = sun.finish().unwrap_err();
+        assert_eq!((e.line, e.msg.as_str()), (3, "clave desconocida 'colour' en [sun]"));
+
+        let mut root = parse("\n\nsize = \"grande\"").unwrap().remove(0);
+        let e = root.get::<f32>("size").err().unwrap();
+        assert_eq!((e.line, e.msg.as_str()), (3, "'size' debe ser un número"));
+        let e = root.need::<f32>("missing").err().unwrap();
+        assert_eq!(e.msg, "falta 'missing' en la raíz");
+        assert_eq!(build_err("[sun]\nmix = 1").0, 2);
+    }
+
+    #[test]
+    fn rejects_degenerate_sun_and_fov() {
+        assert_eq!(build_err("[sun]\n\ndir = [0, 0, 0]"), (3, "dir no puede ser [0, 0, 0]".to_string()));
+        let cam = |fov: &str| format!("[camera]\neye = [1, 1, 1]\ntarget = [0, 0, 0]\nfov = {}", fov);
+        for bad in ["0", "180", "-10", "200"] {
+            assert_eq!(build_err(&cam(bad)), (4, "fov debe estar entre 0 y 180 grados".to_string()), "fov = {}", bad);
+        }
+        let key = "[[keyframe]]\ntime = 0\neye = [1, 1, 1]\ntarget = [0, 0, 0]\nfov = 190";
+        assert_eq!(build_err(key).0, 5);
+    }
+}
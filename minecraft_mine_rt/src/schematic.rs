@@ -0,0 +1,191 @@
+use std::collections::HashSet;
+use std::io;
+
+use crate::aabb::Aabb;
+use crate::block::{self, BlockId, BlockRegistry, Facing};
+use crate::math::Vec3;
+use crate::nbt::{self, Tag};
+use crate::world::World;
+
+// Estructuras exportadas del juego: `.nbt` del bloque de estructuras (vanilla)
+// y esquemas `.schem` de Sponge (versiones 1, 2 y 3).
+
+/// Bloques que no generan geometría.
+pub const AIR: [&str; 4] = ["minecraft:air", "minecraft:cave_air", "minecraft:void_air", "minecraft:structure_void"];
+
+#[derive(Clone)]
+pub struct Schematic {
+    /// Tamaño en bloques (x, y, z)
+    pub size: [i32; 3],
+    /// Nombres de estado con las propiedades que cambian la forma
+    /// (`minecraft:oak_stairs[facing=east,half=bottom]`, ver `block::state_name`)
+    pub palette: Vec<String>,
+    /// Posición relativa e índice en la paleta
+    pub blocks: Vec<([i32; 3], usize)>,
+}
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+/// Carga un `.nbt` de estructura o un `.schem`; el formato se detecta por el contenido.
+pub fn load(path: &str) -> io::Result<Schematic> {
+    let (_, root) = nbt::load(path)?;
+    if root.get("blocks").is_some() {
+        structure(&root)
+    } else if let Some(s) = root.get("Schematic") {
+        sponge(s) // v3: todo bajo un compound "Schematic"
+    } else if root.get("BlockData").is_some() {
+        sponge(&root)
+    } else {
+        Err(bad("no parece una estructura .nbt ni un .schem"))
+    }
+}
+
+/// Nombre de estado de una entrada de paleta (`Name` y `Properties`).
+pub fn state_name(entry: &Tag) -> Option<String> {
+    let name = entry.get("Name").and_then(Tag::as_str)?;
+    let props = entry.get("Properties").and_then(Tag::as_compound).into_iter().flatten();
+    Some(block::state_name(name, props.filter_map(|(k, v)| Some((k.as_str(), v.as_str()?)))))
+}
+
+fn int3(t: Option<&Tag>, what: &str) -> io::Result<[i32; 3]> {
+    let v = t.and_then(Tag::as_ints).filter(|v| v.len() == 3).ok_or_else(|| bad(&format!("falta {}", what)))?;
+    Ok([v[0] as i32, v[1] as i32, v[2] as i32])
+}
+
+/// Formato del bloque de estructuras: `size`, `palette` (o `palettes`) y `blocks`.
+fn structure(root: &Tag) -> io::Result<Schematic> {
+    let size = int3(root.get("size"), "size")?;
+    // con varias paletas (naufragios, etc.) se usa la primera
+    let palette = root.get("palette")
+        .or_else(|| root.get("palettes").and_then(Tag::as_list).and_then(|l| l.first()))
+        .and_then(Tag::as_list)
+        .ok_or_else(|| bad("falta palette"))?;
+    let palette = palette.iter()
+        .map(|s| state_name(s).ok_or_else(|| bad("estado sin Name")))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    let list = root.get("blocks").and_then(Tag::as_list).ok_or_else(|| bad("blocks no es una lista"))?;
+    let mut blocks = Vec::with_capacity(list.len());
+    for b in list {
+        let state = b.get("state").and_then(Tag::as_int).ok_or_else(|| bad("bloque sin state"))? as usize;
+        if state >= palette.len() { return Err(bad("state fuera de la paleta")); }
+        blocks.push((int3(b.get("pos"), "pos")?, state));
+    }
+    Ok(Schematic { size, palette, blocks })
+}
+
+/// Sponge: paleta nombre -> índice y datos en varints, índice = (y * largo + z) * ancho + x.
+fn sponge(s: &Tag) -> io::Result<Schematic> {
+    let dim = |k: &str| s.get(k).and_then(Tag::as_int).map(|v| v as u16 as i32).ok_or_else(|| bad(&format!("falta {}", k)));
+    let size = [dim("Width")?, dim("Height")?, dim("Length")?];
+    let (pal, data) = match s.get("Blocks") {
+        Some(b) => (b.get("Palette"), b.get("Data")), // v3
+        None => (s.get("Palette"), s.get("BlockData")),
+    };
+    let pal = pal.and_then(Tag::as_compound).ok_or_else(|| bad("falta Palette"))?;
+    let mut palette = vec![String::new(); pal.len()];
+    for (key, idx) in pal {
+        let i = idx.as_int().filter(|&i| i >= 0 && (i as usize) < pal.len()).ok_or_else(|| bad("índice de paleta inválido"))?;
+        // "minecraft:oak_stairs[facing=east,half=bottom,shape=straight]" -> solo las que cambian la forma
+        palette[i as usize] = match key.split_once('[') {
+            Some((name, props)) => block::state_name(name, props.trim_end_matches(']').split(',').filter_map(|p| p.split_once('='))),
+            None => key.clone(),
+        };
+    }
+
+    let data = match data {
+        Some(Tag::ByteArray(d)) => d,
+        _ => return Err(bad("faltan los datos de bloques")),
+    };
+    // cada varint ocupa al menos un byte: un tamaño mayor que los datos es inválido
+    let total = size.iter().try_fold(1usize, |acc, &d| acc.checked_mul(d as usize))
+        .filter(|&t| t <= data.len())
+        .ok_or_else(|| bad("el tamaño no cabe en BlockData"))?;
+    let mut blocks = Vec::with_capacity(total);
+    let mut bytes = data.iter().map(|&b| b as u8);
+    for i in 0..total {
+        // varint LEB128
+        let (mut v, mut shift) = (0usize, 0);
+        loop {
+            let b = bytes.next().ok_or_else(|| bad("BlockData truncado"))?;
+            v |= ((b & 0x7f) as usize) << shift;
+            if b & 0x80 == 0 { break; }
+            shift += 7;
+            if shift > 28 { return Err(bad("varint demasiado largo")); }
+        }
+        if v >= palette.len() { return Err(bad("índice fuera de la paleta")); }
+        let (x, z, y) = (i as i32 % size[0], i as i32 / size[0] % size[2], i as i32 / (size[0] * size[2]));
+        blocks.push(([x, y, z], v));
+    }
+    Ok(Schematic { size, palette, blocks })
+}
+
+/// Cambia la propiedad `facing` de un nombre de estado para una estructura girada o reflejada.
+fn oriented(name: &str, turns: i32, mirror: bool) -> String {
+    let Some((base, props)) = name.split_once('[') else { return name.to_string() };
+    let props: Vec<String> = props.trim_end_matches(']').split(',').map(|p| match p.split_once('=') {
+        Some(("facing", v)) if let Some(f) = Facing::from_name(v) => format!("facing={}", f.oriented(turns, mirror).name()),
+        _ => p.to_string(),
+    }).collect();
+    format!("{}[{}]", base, props.join(","))
+}
+
+impl Schematic {
+    /// Posición (x, z) de un bloque tras reflejar la estructura en x (si `mirror`)
+    /// y girarla `rotation` grados (0, 90, 180, 270) en sentido horario visto desde arriba.
+    /// Vale también para puntos fuera de la caja, como la entrada delante de una puerta.
+    pub fn orient(&self, rotation: i32, mirror: bool) -> impl Fn(i32, i32) -> (i32, i32) {
+        let [sx, _, sz] = self.size;
+        let turns = rotation.rem_euclid(360) / 90;
+        move |x, z| {
+            let x = if mirror { sx - 1 - x } else { x };
+            match turns {
+                1 => (sz - 1 - z, x),
+                2 => (sx - 1 - x, sz - 1 - z),
+                3 => (z, sx - 1 - x),
+                _ => (x, z),
+            }
+        }
+    }
+
+    /// Bloque del registro para cada entrada de la paleta, con `BlockRegistry::resolve`
+    /// y el `facing` girado como la estructura; el aire y los nombres desconocidos
+    /// quedan en `None` y estos últimos se avisan una vez.
+    fn ids(&self, reg: &BlockRegistry, rotation: i32, mirror: bool) -> Vec<Option<BlockId>> {
+        let mut missing = HashSet::new();
+        self.palette.iter().map(|name| {
+            if AIR.contains(&name.as_str()) { return None; }
+            let id = reg.resolve(&oriented(name, rotation.rem_euclid(360) / 90, mirror));
+            if id.is_none() && missing.insert(name) {
+                eprintln!("aviso: bloque '{}' sin equivalente en el registro, se omite", name);
+            }
+            id
+        }).collect()
+    }
+
+    /// Bloques con la esquina mínima de la estructura (ya orientada) en `at`.
+    pub fn place(&self, reg: &BlockRegistry, at: Vec3, rotation: i32, mirror: bool) -> Vec<Aabb> {
+        let rot = self.orient(rotation, mirror);
+        let ids = self.ids(reg, rotation, mirror);
+        let mut cubes = Vec::with_capacity(self.blocks.len());
+        for &([x, y, z], state) in &self.blocks {
+            let Some(id) = ids[state] else { continue };
+            let (rx, rz) = rot(x, z);
+            reg.place(id, at.x + rx as f32, at.y + y as f32, at.z + rz as f32, &mut cubes);
+        }
+        cubes
+    }
+
+    /// Copia la estructura en la rejilla. El aire explícito vacía el terreno
+    /// (interiores, túneles); `structure_void` y los bloques desconocidos no tocan nada.
+    pub fn stamp(&self, reg: &BlockRegistry, world: &mut World, at: [i32; 3], rotation: i32, mirror: bool) {
+        let rot = self.orient(rotation, mirror);
+        let ids = self.ids(reg, rotation, mirror);
+        for &([x, y, z], state) in &self.blocks {
+            let name = self.palette[state].as_str();
+            if name == "minecraft:structure_void" || (ids[state].is_none() && !AIR.contains(&name)) { continue; }
+            let (rx, rz) = rot(x, z);
+            world.set_block(at[0] + rx, at[1] + y, at[2] + rz, ids[state]);
+        }
+    }
+}
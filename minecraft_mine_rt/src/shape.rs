@@ -0,0 +1,153 @@
+use crate::aabb::{face_from_normal, Hit, Tint};
+use crate::bvh::Bvh;
+use crate::math::{Ray, Vec3};
+
+// Primitivas además de las cajas: esferas, planos infinitos, triángulos y mallas.
+// Todas devuelven el mismo `Hit` que una caja (con UV y la cara según la normal),
+// así el sombreado no distingue de dónde viene el impacto.
+
+/// Aspecto de la superficie, como en `Aabb`: material, texturas por cara y tinte.
+#[derive(Clone)]
+pub struct Surface {
+    pub mat_id: usize,
+    pub face_tex: Option<[usize; 6]>,
+    pub tint: Option<Tint>,
+}
+
+impl Surface {
+    fn hit(&self, t: f32, p: Vec3, n: Vec3, (u, v): (f32, f32)) -> Hit {
+        Hit { t, p, n, u, v, mat_id: self.mat_id, face_idx: face_from_normal(n), face_tex: self.face_tex, tint: self.tint }
+    }
+}
+
+fn min3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)) }
+fn max3(a: Vec3, b: Vec3) -> Vec3 { Vec3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)) }
+
+#[derive(Clone)]
+pub struct Triangle {
+    pub p: [Vec3; 3],
+    /// Normales por vértice para suavizar; sin ellas se usa la del plano (p1 - p0) x (p2 - p0)
+    pub n: Option<[Vec3; 3]>,
+    pub uv: [(f32, f32); 3],
+    pub surface: Surface,
+}
+
+impl Triangle {
+    /// Möller-Trumbore; la normal no se gira hacia el rayo.
+    fn hit(&self, ray: &Ray) -> Option<Hit> {
+        let [p0, p1, p2] = self.p;
+        let (e1, e2) = (p1.sub(p0), p2.sub(p0));
+        let q = ray.d.cross(e2);
+        let det = e1.dot(q);
+        if det.abs() < 1e-12 { return None; }
+        let inv = 1.0 / det;
+        let s = ray.o.sub(p0);
+        let b1 = s.dot(q) * inv;
+        if !(0.0..=1.0).contains(&b1) { return None; }
+        let r = s.cross(e1);
+        let b2 = ray.d.dot(r) * inv;
+        if b2 < 0.0 || b1 + b2 > 1.0 { return None; }
+        let t = e2.dot(r) * inv;
+        if t <= 0.0 { return None; }
+        let b0 = 1.0 - b1 - b2;
+        let n = match self.n {
+            Some([n0, n1, n2]) => n0.mul(b0).add(n1.mul(b1)).add(n2.mul(b2)).norm(),
+            None => e1.cross(e2).norm(),
+        };
+        let [a, b, c] = self.uv;
+        let uv = (a.0 * b0 + b.0 * b1 + c.0 * b2, a.1 * b0 + b.1 * b1 + c.1 * b2);
+        Some(self.surface.hit(t, ray.o.add(ray.d.mul(t)), n, uv))
+    }
+
+    fn bounds(&self) -> (Vec3, Vec3) {
+        let [a, b, c] = self.p;
+        (min3(min3(a, b), c), max3(max3(a, b), c))
+    }
+}
+
+/// Triángulos con su propia BVH.
+pub struct Mesh {
+    tris: Vec<Triangle>,
+    bvh: Bvh,
+    min: Vec3,
+    max: Vec3,
+}
+
+impl Mesh {
+    pub fn new(tris: Vec<Triangle>) -> Self {
+        let bounds: Vec<(Vec3, Vec3)> = tris.iter().map(Triangle::bounds).collect();
+        let first = bounds.first().copied().unwrap_or_default();
+        let (min, max) = bounds.iter().fold(first, |(a, b), c| (min3(a, c.0), max3(b, c.1)));
+        Self { bvh: Bvh::build(&bounds), tris, min, max }
+    }
+
+    fn hit(&self, ray: &Ray) -> Option<Hit> {
+        let mut best: Option<Hit> = None;
+        self.bvh.visit(ray, |i| {
+            let h = self.tris[i].hit(ray)?;
+            if best.as_ref().is_some_and(|b| b.t <= h.t) { return None; }
+            let t = h.t;
+            best = Some(h);
+            Some(t)
+        });
+        best
+    }
+}
+
+pub enum Shape {
+    Sphere { center: Vec3, radius: f32, surface: Surface },
+    /// Plano infinito por `point`; la textura se repite cada unidad, como un bloque
+    Plane { point: Vec3, normal: Vec3, surface: Surface },
+    Triangle(Triangle),
+    Mesh(Mesh),
+}
+
+impl Shape {
+    /// Caja envolvente; la del plano es infinita y la BVH lo prueba siempre.
+    pub fn bounds(&self) -> (Vec3, Vec3) {
+        match self {
+            Shape::Sphere { center, radius, .. } => {
+                let r = Vec3::new(*radius, *radius, *radius);
+                (center.sub(r), center.add(r))
+            }
+            Shape::Plane { .. } => (Vec3::new(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY), Vec3::new(f32::INFINITY, f32::INFINITY, f32::INFINITY)),
+            Shape::Triangle(tri) => tri.bounds(),
+            Shape::Mesh(m) => (m.min, m.max),
+        }
+    }
+
+    pub fn hit(&self, ray: &Ray) -> Option<Hit> {
+        match self {
+            Shape::Sphere { center, radius, surface } => {
+                let oc = ray.o.sub(*center);
+                let (b, c) = (oc.dot(ray.d), oc.dot(oc) - radius * radius);
+                let a = ray.d.dot(ray.d);
+                let disc = b * b - a * c;
+                if disc < 0.0 { return None; }
+                // la raíz más cercana delante del origen (la de salida si está dentro)
+                let sq = disc.sqrt();
+                let t = [(-b - sq) / a, (-b + sq) / a].into_iter().find(|&t| t > 0.0)?;
+                let p = ray.o.add(ray.d.mul(t));
+                let n = p.sub(*center).mul(1.0 / radius);
+                let u = 0.5 + n.z.atan2(n.x) / std::f32::consts::TAU;
+                let v = 0.5 + n.y.clamp(-1.0, 1.0).asin() / std::f32::consts::PI;
+                Some(surface.hit(t, p, n, (u, v)))
+            }
+            Shape::Plane { point, normal, surface } => {
+                let dn = ray.d.dot(*normal);
+                if dn.abs() < 1e-9 { return None; }
+                let t = point.sub(ray.o).dot(*normal) / dn;
+                if t <= 0.0 { return None; }
+                let p = ray.o.add(ray.d.mul(t));
+                // ejes de la textura sobre el plano
+                let up = if normal.y.abs() > 0.9 { Vec3::new(0.0, 0.0, 1.0) } else { Vec3::new(0.0, 1.0, 0.0) };
+                let tu = normal.cross(up).norm();
+                let tv = tu.cross(*normal);
+                let d = p.sub(*point);
+                Some(surface.hit(t, p, *normal, (d.dot(tu).rem_euclid(1.0), d.dot(tv).rem_euclid(1.0))))
+            }
+            Shape::Triangle(tri) => tri.hit(ray),
+            Shape::Mesh(m) => m.hit(ray),
+        }
+    }
+}
@@ -2,6 +2,9 @@ use crate::color::Color;
 use crate::math::Vec3;
 use crate::ppm::load_ppm;
 
+/// Archivos de las caras en la carpeta del cubemap, en el orden de `faces`.
+pub const FACE_NAMES: [&str; 6] = ["posx", "negx", "posy", "negy", "posz", "negz"];
+
 /// Cubemap simple: 6 caras en RGB8.
 /// Orden esperado de archivos: +X, -X, +Y, -Y, +Z, -Z.
 pub struct CubeMap {
@@ -12,11 +15,10 @@ pub struct CubeMap {
 
 impl CubeMap {
     pub fn from_folder(path: &str) -> Option<Self> {
-        let names = ["posx", "negx", "posy", "negy", "posz", "negz"];
         let mut data: [Vec<u8>; 6] = Default::default();
         let mut w = 0usize; let mut h = 0usize;
 
-        for (i, n) in names.iter().enumerate() {
+        for (i, n) in FACE_NAMES.iter().enumerate() {
             let p = format!("{}/{}.ppm", path, n);
             let img = load_ppm(&p).ok()?;
             if i == 0 { w = img.w; h = img.h; }
@@ -74,6 +76,19 @@ impl CubeMap {
         (face, u.clamp(0.0,1.0), v.clamp(0.0,1.0))
     }
 
+    /// Inversa de `dir_to_face_uv`: dirección (sin normalizar) del punto `u,v` de la cara.
+    pub fn face_dir(face: usize, u: f32, v: f32) -> Vec3 {
+        let (uc, vc) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+        match face {
+            0 => Vec3::new(1.0, -vc, -uc),
+            1 => Vec3::new(-1.0, -vc, uc),
+            2 => Vec3::new(uc, 1.0, vc),
+            3 => Vec3::new(uc, -1.0, -vc),
+            4 => Vec3::new(uc, -vc, 1.0),
+            _ => Vec3::new(-uc, -vc, -1.0),
+        }
+    }
+
     pub fn sample(&self, dir: Vec3) -> Color {
         let (face, u, v) = Self::dir_to_face_uv(dir);
         let x = (u * self.w as f32) as usize % self.w;
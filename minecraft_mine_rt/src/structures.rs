@@ -0,0 +1,391 @@
+use std::f32::consts::TAU;
+
+use crate::block::BlockRegistry;
+use crate::rng::Rng;
+use crate::schematic::Schematic;
+use crate::world::World;
+
+// Estructuras sobre el terreno generado: aldeas (pozo, casas y caminos),
+// ruinas medio enterradas y galerías de mina bajo tierra.
+
+/// Tipos de estructura que se reparten por el mapa.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Kind {
+    Village,
+    Ruins,
+    Mineshaft,
+}
+
+impl Kind {
+    pub fn by_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "village" => Kind::Village,
+            "ruins" => Kind::Ruins,
+            "mineshaft" => Kind::Mineshaft,
+            _ => return None,
+        })
+    }
+}
+
+pub struct Params {
+    pub seed: u64,
+    pub kinds: Vec<Kind>,
+    /// Casas importadas (.nbt, .schem) que se suman a las de código en las aldeas;
+    /// la puerta se supone en el centro de la cara z = 0.
+    pub houses: Vec<Schematic>,
+}
+
+impl Params {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, kinds: vec![Kind::Village, Kind::Ruins, Kind::Mineshaft], houses: Vec::new() }
+    }
+}
+
+/// Plantilla con el bloque de delante de la puerta (x, z relativos, normalmente z = -1)
+/// y el bloque de los cimientos.
+struct Template {
+    schem: Schematic,
+    door: [i32; 2],
+    foundation: &'static str,
+}
+
+/// Plantillas de código: una rejilla densa de nombres que se vuelca en un `Schematic`.
+struct Builder {
+    size: [i32; 3],
+    palette: Vec<String>,
+    cells: Vec<usize>,
+}
+
+impl Builder {
+    /// `fill` es el contenido inicial: "air" vacía el terreno, "structure_void" lo respeta.
+    fn new(size: [i32; 3], fill: &str) -> Self {
+        let n = (size[0] * size[1] * size[2]) as usize;
+        Self { size, palette: vec![format!("minecraft:{}", fill)], cells: vec![0; n] }
+    }
+
+    fn set(&mut self, [x, y, z]: [i32; 3], name: &str) {
+        let [sx, sy, sz] = self.size;
+        if x < 0 || y < 0 || z < 0 || x >= sx || y >= sy || z >= sz { return; }
+        let name = format!("minecraft:{}", name);
+        let state = self.palette.iter().position(|p| *p == name).unwrap_or_else(|| {
+            self.palette.push(name);
+            self.palette.len() - 1
+        });
+        self.cells[((y * sz + z) * sx + x) as usize] = state;
+    }
+
+    /// Caja con las dos esquinas incluidas.
+    fn fill(&mut self, a: [i32; 3], b: [i32; 3], name: &str) {
+        for y in a[1]..=b[1] {
+            for z in a[2]..=b[2] {
+                for x in a[0]..=b[0] { self.set([x, y, z], name); }
+            }
+        }
+    }
+
+    fn build(self) -> Schematic {
+        let [sx, _, sz] = self.size;
+        let blocks = self.cells.iter().enumerate().map(|(i, &s)| {
+            let i = i as i32;
+            ([i % sx, i / (sx * sz), i / sx % sz], s)
+        }).collect();
+        Schematic { size: self.size, palette: self.palette, blocks }
+    }
+}
+
+/// Casa de tablones con esquinas de tronco, suelo de piedra y tejado escalonado.
+fn house(w: i32, d: i32) -> Template {
+    let mut b = Builder::new([w, 6, d], "air");
+    b.fill([0, 0, 0], [w - 1, 0, d - 1], "cobblestone");
+    b.fill([0, 1, 0], [w - 1, 3, d - 1], "oak_planks");
+    b.fill([1, 1, 1], [w - 2, 3, d - 2], "air");
+    for [x, z] in [[0, 0], [w - 1, 0], [0, d - 1], [w - 1, d - 1]] { b.fill([x, 1, z], [x, 3, z], "oak_log"); }
+    b.fill([0, 4, 0], [w - 1, 4, d - 1], "stone_bricks");
+    b.fill([1, 5, 1], [w - 2, 5, d - 2], "stone_bricks");
+    let door = w / 2;
+    b.fill([door, 1, 0], [door, 2, 0], "air");
+    // ventanas en los laterales y al fondo
+    b.set([0, 2, d / 2], "glass_pane");
+    b.set([w - 1, 2, d / 2], "glass_pane");
+    b.set([door, 2, d - 1], "glass_pane");
+    if w > 5 {
+        b.set([1, 2, 0], "glass_pane");
+        b.set([w - 2, 2, 0], "glass_pane");
+    }
+    Template { schem: b.build(), door: [door, -1], foundation: "cobblestone" }
+}
+
+/// Huerto: tierra labrada en dos bancales con una acequia entre ellos y borde de troncos.
+fn farm() -> Template {
+    let mut b = Builder::new([7, 2, 5], "air");
+    b.fill([0, 0, 0], [6, 0, 4], "oak_log");
+    b.fill([1, 0, 1], [5, 0, 3], "farmland");
+    b.fill([3, 0, 1], [3, 0, 3], "water");
+    b.fill([1, 1, 1], [2, 1, 3], "short_grass");
+    b.fill([4, 1, 1], [5, 1, 3], "short_grass");
+    Template { schem: b.build(), door: [3, -1], foundation: "dirt" }
+}
+
+/// Pozo del centro de la aldea.
+fn well() -> Template {
+    let mut b = Builder::new([4, 5, 4], "air");
+    b.fill([0, 0, 0], [3, 1, 3], "cobblestone");
+    b.fill([1, 0, 1], [2, 1, 2], "water");
+    for [x, z] in [[0, 0], [3, 0], [0, 3], [3, 3]] { b.fill([x, 2, z], [x, 3, z], "oak_log"); }
+    b.fill([0, 4, 0], [3, 4, 3], "cobblestone");
+    Template { schem: b.build(), door: [1, -1], foundation: "cobblestone" }
+}
+
+/// Ruina: muros de altura irregular y suelo con huecos; lo que falta conserva el terreno.
+fn ruin(rng: &mut Rng) -> Template {
+    let (w, d) = (5 + rng.below(4) as i32, 5 + rng.below(4) as i32);
+    let mut b = Builder::new([w, 5, d], "structure_void");
+    let stone = |rng: &mut Rng| if rng.f32() < 0.4 { "mossy_cobblestone" } else { "cobblestone" };
+    for z in 0..d {
+        for x in 0..w {
+            if rng.f32() < 0.75 { b.set([x, 0, z], if rng.f32() < 0.5 { "stone_bricks" } else { "mossy_stone_bricks" }); }
+            if x == 0 || z == 0 || x == w - 1 || z == d - 1 {
+                let h = rng.below(5) as i32;
+                for y in 1..=h {
+                    let s = stone(rng);
+                    b.set([x, y, z], s);
+                }
+            } else {
+                b.fill([x, 1, z], [x, 4, z], "air");
+            }
+        }
+    }
+    Template { schem: b.build(), door: [w / 2, -1], foundation: "cobblestone" }
+}
+
+/// Tramo de galería a lo largo de +z con un marco de troncos y tablones en medio.
+fn corridor() -> Schematic {
+    let mut b = Builder::new([3, 3, 5], "air");
+    b.fill([0, 0, 2], [0, 1, 2], "oak_log");
+    b.fill([2, 0, 2], [2, 1, 2], "oak_log");
+    b.fill([0, 2, 2], [2, 2, 2], "oak_planks");
+    b.build()
+}
+
+/// Sala de cruce de las galerías, con suelo de tierra.
+fn crossing() -> Schematic {
+    let mut b = Builder::new([5, 4, 5], "air");
+    b.fill([0, 0, 0], [4, 0, 4], "dirt");
+    b.build()
+}
+
+/// Rectángulo ocupado en planta: esquina mínima y máxima excluida.
+#[derive(Clone, Copy)]
+struct Rect {
+    x0: i32,
+    z0: i32,
+    x1: i32,
+    z1: i32,
+}
+
+impl Rect {
+    fn contains(&self, x: i32, z: i32) -> bool { x >= self.x0 && x < self.x1 && z >= self.z0 && z < self.z1 }
+
+    fn overlaps(&self, o: &Rect, margin: i32) -> bool {
+        self.x0 < o.x1 + margin && o.x0 < self.x1 + margin && self.z0 < o.z1 + margin && o.z0 < self.z1 + margin
+    }
+}
+
+struct Placer<'a> {
+    world: &'a mut World,
+    reg: &'a BlockRegistry,
+    rng: Rng,
+    taken: Vec<Rect>,
+}
+
+/// Coloca las estructuras de `p.kinds` en el terreno, antes de la vegetación.
+pub fn place(world: &mut World, reg: &BlockRegistry, p: &Params) -> Result<(), String> {
+    for name in ["cobblestone", "oak_planks", "oak_log", "stone_bricks", "dirt_path"] {
+        if reg.resolve(name).is_none() {
+            return Err(format!("las estructuras necesitan un bloque para '{}'", name));
+        }
+    }
+    let mut s = Placer { world, reg, rng: Rng::new(p.seed ^ 0x57ac_7e5e), taken: Vec::new() };
+    for kind in &p.kinds {
+        match kind {
+            Kind::Village => s.village(&p.houses),
+            Kind::Ruins => {
+                for _ in 0..1 + s.rng.below(2) {
+                    let t = ruin(&mut s.rng);
+                    s.try_site(&t, 30, -1);
+                }
+            }
+            Kind::Mineshaft => s.mineshaft(),
+        }
+    }
+    Ok(())
+}
+
+impl Placer<'_> {
+    /// Altura del bloque más alto de la columna, salvo si es agua.
+    fn ground(&self, x: i32, z: i32) -> Option<i32> {
+        let water = self.reg.find("water");
+        let y = (0..self.world.size()[1]).rev().find(|&y| self.world.get_block(x, y, z).is_some())?;
+        (self.world.get_block(x, y, z) != water).then_some(y)
+    }
+
+    /// Altura base para una planta si el suelo es firme y casi llano: la media redondeada.
+    fn level(&self, r: &Rect) -> Option<i32> {
+        let [sx, _, sz] = self.world.size();
+        if r.x0 < 0 || r.z0 < 0 || r.x1 > sx || r.z1 > sz { return None; }
+        let (mut lo, mut hi, mut sum, mut n) = (i32::MAX, i32::MIN, 0, 0);
+        for z in r.z0..r.z1 {
+            for x in r.x0..r.x1 {
+                let y = self.ground(x, z)?;
+                (lo, hi, sum, n) = (lo.min(y), hi.max(y), sum + y, n + 1);
+            }
+        }
+        (hi - lo <= 3).then(|| (sum as f32 / n as f32).round() as i32)
+    }
+
+    /// Planta ocupada por la plantilla orientada con la esquina mínima en (x, z).
+    fn rect(t: &Template, x: i32, z: i32, rotation: i32) -> Rect {
+        let [sx, _, sz] = t.schem.size;
+        let (w, d) = if rotation % 180 == 0 { (sx, sz) } else { (sz, sx) };
+        Rect { x0: x, z0: z, x1: x + w, z1: z + d }
+    }
+
+    /// Cimientos hasta el primer sólido bajo cada columna, terreno sobrante
+    /// por encima retirado y la plantilla copiada en la rejilla.
+    fn build(&mut self, t: &Template, r: Rect, y: i32, rotation: i32, mirror: bool) {
+        let foundation = self.reg.resolve(t.foundation);
+        let water = self.reg.find("water");
+        let top = y + t.schem.size[1];
+        for z in r.z0..r.z1 {
+            for x in r.x0..r.x1 {
+                for fy in (0..y).rev() {
+                    let b = self.world.get_block(x, fy, z);
+                    if b.is_some() && b != water { break; }
+                    self.world.set_block(x, fy, z, foundation);
+                }
+                for cy in top..self.world.size()[1] { self.world.set_block(x, cy, z, None); }
+            }
+        }
+        t.schem.stamp(self.reg, self.world, [r.x0, y, r.z0], rotation, mirror);
+        self.taken.push(r);
+    }
+
+    /// Prueba posiciones al azar hasta encontrar suelo llano y libre; devuelve la planta.
+    fn try_site(&mut self, t: &Template, tries: u32, sink: i32) -> Option<Rect> {
+        let [sx, _, sz] = self.world.size();
+        for _ in 0..tries {
+            let rotation = self.rng.below(4) as i32 * 90;
+            let r = Self::rect(t, self.rng.below(sx.max(1) as usize) as i32, self.rng.below(sz.max(1) as usize) as i32, rotation);
+            if self.taken.iter().any(|o| o.overlaps(&r, 2)) { continue; }
+            let Some(y) = self.level(&r) else { continue };
+            let mirror = self.rng.f32() < 0.5;
+            self.build(t, r, y + sink, rotation, mirror);
+            return Some(r);
+        }
+        None
+    }
+
+    /// Pozo en un claro y casas alrededor con la puerta hacia él, unidas por caminos.
+    fn village(&mut self, imported: &[Schematic]) {
+        let well = well();
+        let Some(center) = self.try_site(&well, 60, 0) else { return };
+        let (cx, cz) = ((center.x0 + center.x1) / 2, (center.z0 + center.z1) / 2);
+
+        let mut pool = vec![house(5, 5), house(7, 6), house(5, 7), farm()];
+        pool.extend(imported.iter().map(|s| Template {
+            schem: s.clone(),
+            door: [s.size[0] / 2, -1],
+            foundation: "cobblestone",
+        }));
+        let want = 3 + self.rng.below(4);
+        let mut doors = Vec::new();
+        for _ in 0..80 {
+            if doors.len() >= want { break; }
+            let t = &pool[self.rng.below(pool.len())];
+            let (a, dist) = (self.rng.range(0.0, TAU), self.rng.range(7.0, 15.0));
+            let (hx, hz) = (cx + (a.cos() * dist) as i32, cz + (a.sin() * dist) as i32);
+            // la puerta (cara z = -1 sin girar) mira hacia el pozo
+            let (dx, dz) = (cx - hx, cz - hz);
+            let rotation = if dx.abs() > dz.abs() { if dx > 0 { 90 } else { 270 } } else if dz > 0 { 180 } else { 0 };
+            let r = Self::rect(t, 0, 0, rotation);
+            let r = Self::rect(t, hx - r.x1 / 2, hz - r.z1 / 2, rotation);
+            if self.taken.iter().any(|o| o.overlaps(&r, 2)) { continue; }
+            let Some(y) = self.level(&r) else { continue };
+            let mirror = self.rng.f32() < 0.5;
+            self.build(t, r, y, rotation, mirror);
+            let (ox, oz) = t.schem.orient(rotation, mirror)(t.door[0], t.door[1]);
+            doors.push((r.x0 + ox, r.z0 + oz));
+        }
+        for (x, z) in doors { self.path(x, z, cx, cz); }
+    }
+
+    /// Camino de tierra desde (x, z) hasta el objetivo, avanzando por el eje con más
+    /// distancia pendiente y rodeando las plantas ocupadas; sobre el agua, puente de tablones.
+    fn path(&mut self, mut x: i32, mut z: i32, tx: i32, tz: i32) {
+        let (path, planks) = (self.reg.resolve("dirt_path"), self.reg.resolve("oak_planks"));
+        let water = self.reg.find("water");
+        let goal = self.taken.iter().copied().find(|r| r.contains(tx, tz));
+        for _ in 0..256 {
+            if goal.is_some_and(|g| g.contains(x, z)) { break; }
+            if !self.taken.iter().any(|r| r.contains(x, z)) {
+                let Some(y) = (0..self.world.size()[1]).rev().find(|&y| self.world.get_block(x, y, z).is_some()) else { break };
+                let b = if self.world.get_block(x, y, z) == water { planks } else { path };
+                self.world.set_block(x, y, z, b);
+            }
+            let (dx, dz) = ((tx - x).signum(), (tz - z).signum());
+            let free = |px: i32, pz: i32| goal.is_some_and(|g| g.contains(px, pz)) || !self.taken.iter().any(|r| r.contains(px, pz));
+            let steps = if (tx - x).abs() >= (tz - z).abs() { [(dx, 0), (0, dz)] } else { [(0, dz), (dx, 0)] };
+            let Some(&(sx, sz)) = steps.iter().find(|&&(sx, sz)| (sx, sz) != (0, 0) && free(x + sx, z + sz)) else { break };
+            (x, z) = (x + sx, z + sz);
+        }
+    }
+
+    /// Sala de cruce a media profundidad y galerías que se ramifican desde ella.
+    fn mineshaft(&mut self) {
+        let [sx, sy, sz] = self.world.size();
+        if sy < 12 || sx < 16 || sz < 16 { return; }
+        // bajo tierra firme, con al menos seis bloques de techo
+        let mut site = None;
+        for _ in 0..30 {
+            let (x, z) = (4 + self.rng.below((sx - 8) as usize) as i32, 4 + self.rng.below((sz - 8) as usize) as i32);
+            if let Some(ground) = self.ground(x, z).filter(|&g| g >= 9) {
+                site = Some((x, z, ground));
+                break;
+            }
+        }
+        let Some((x, z, ground)) = site else { return };
+        let y = 2 + self.rng.below((ground - 8) as usize) as i32;
+        crossing().stamp(self.reg, self.world, [x - 2, y - 1, z - 2], 0, false);
+
+        let seg = corridor();
+        let mut open: Vec<([i32; 2], i32, u32)> = [0, 90, 180, 270].iter().map(|&r| {
+            let (dx, dz) = dir(&seg, r);
+            ([x + dx * 3, z + dz * 3], r, 0)
+        }).collect();
+        while let Some(([px, pz], rotation, depth)) = open.pop() {
+            let rot = seg.orient(rotation, false);
+            let (ex, ez) = rot(1, 0);
+            let (lx, lz) = rot(1, seg.size[2] - 1);
+            let at = [px - ex, y, pz - ez];
+            if at[0] < 0 || at[2] < 0 || at[0] + lx.max(ex) >= sx || at[2] + lz.max(ez) >= sz { continue; }
+            seg.stamp(self.reg, self.world, at, rotation, false);
+            if depth >= 6 { continue; }
+            let (dx, dz) = dir(&seg, rotation);
+            let next = [at[0] + lx + dx, at[2] + lz + dz];
+            // sigue recto casi siempre y a veces se abre a un lado
+            if self.rng.f32() < 0.85 { open.push((next, rotation, depth + 1)); }
+            if self.rng.f32() < 0.3 {
+                let side = (rotation + if self.rng.f32() < 0.5 { 90 } else { 270 }) % 360;
+                let (sdx, sdz) = dir(&seg, side);
+                open.push(([at[0] + lx + sdx * 2, at[2] + lz + sdz * 2], side, depth + 1));
+            }
+        }
+    }
+}
+
+/// Dirección en planta de una galería (a lo largo de +z sin girar) tras girarla.
+fn dir(seg: &Schematic, rotation: i32) -> (i32, i32) {
+    let rot = seg.orient(rotation, false);
+    let ((ax, az), (bx, bz)) = (rot(1, 0), rot(1, 1));
+    (bx - ax, bz - az)
+}
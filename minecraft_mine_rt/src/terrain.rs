@@ -0,0 +1,192 @@
+use crate::block::{BlockId, BlockRegistry};
+use crate::noise::Perlin;
+use crate::world::World;
+
+/// Parámetros del generador de paisajes; la misma semilla da siempre el mismo terreno.
+#[derive(Clone)]
+pub struct Params {
+    pub seed: u64,
+    /// Tamaño en bloques (x, z)
+    pub size: [i32; 2],
+    /// Alto de la rejilla
+    pub height: i32,
+    pub octaves: u32,
+    /// Tamaño típico de las colinas, en bloques
+    pub scale: f32,
+    /// Desnivel aproximado sobre y bajo el nivel base
+    pub amplitude: f32,
+    pub sea_level: i32,
+    /// Peso del ruido 3D sobre la altura: 0 = mapa de alturas puro, 1 = salientes marcados
+    pub overhang: f32,
+    /// Cuevas de queso (cavernas) y de gusano (túneles)
+    pub caves: bool,
+    pub ores: bool,
+    /// Las cuevas por debajo de esta altura se llenan de lava
+    pub lava_level: i32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            seed: 1, size: [48, 48], height: 32, octaves: 5, scale: 32.0, amplitude: 10.0, sea_level: 8,
+            overhang: 0.5, caves: true, ores: true, lava_level: 2,
+        }
+    }
+}
+
+/// Bloques de cada estrato, resueltos por nombre en el registro.
+struct Strata {
+    stone: BlockId,
+    dirt: BlockId,
+    grass: BlockId,
+    sand: BlockId,
+    water: BlockId,
+    lava: Option<BlockId>,
+}
+
+impl Strata {
+    fn from(reg: &BlockRegistry) -> Result<Self, String> {
+        let id = |name: &str| reg.find(name).ok_or_else(|| format!("el terreno necesita el bloque '{}'", name));
+        Ok(Self {
+            stone: id("stone")?, dirt: id("dirt")?, grass: id("grass")?, sand: id("sand")?, water: id("water")?,
+            lava: reg.find("lava"),
+        })
+    }
+}
+
+/// Vetas de mineral: bloque, umbral del ruido (más alto = más raro) y altura máxima.
+const ORES: [(&str, f32, i32); 4] = [
+    ("coal_ore", 0.42, i32::MAX),
+    ("iron_ore", 0.46, 24),
+    ("gold_ore", 0.52, 14),
+    ("diamond_ore", 0.56, 8),
+];
+
+impl Params {
+    /// Altura de la superficie en la columna (x, z): fBm 2D sobre el nivel base.
+    pub fn surface(&self, hills: &Perlin, x: i32, z: i32) -> i32 {
+        let n = hills.fbm2(x as f32 / self.scale, z as f32 / self.scale, self.octaves);
+        // el fBm rara vez pasa de ±0.5: se estira para que `amplitude` sea el desnivel real
+        let h = self.sea_level as f32 + 2.0 + self.amplitude * 2.0 * n;
+        // bajo el techo de la rejilla, aunque sea de menos de tres bloques
+        (h.round() as i32).clamp(1, (self.height - 2).max(1))
+    }
+}
+
+/// Genera un paisaje a partir de una función de densidad: la altura del fBm 2D
+/// deformada con ruido 3D (salientes), con estratos de piedra, tierra y césped,
+/// arena en las playas, mar hasta `sea_level`, cuevas y vetas de mineral.
+pub fn generate(p: &Params, reg: &BlockRegistry) -> Result<World, String> {
+    let s = Strata::from(reg)?;
+    let hills = Perlin::new(p.seed);
+    let soil = Perlin::new(p.seed ^ 0x5eed_d1e7);
+    let warp = Perlin::new(p.seed ^ 0x0fe4_4a96);
+    let [sx, sz] = p.size;
+    let mut world = World::new(sx, p.height, sz)?;
+    let mut heights = vec![0; (sx * sz) as usize];
+
+    for z in 0..sz {
+        for x in 0..sx {
+            let h = p.surface(&hills, x, z);
+            heights[(z * sx + x) as usize] = h;
+            let beach = h <= p.sea_level + 1 && h >= p.sea_level - 3;
+            let deep = h < p.sea_level - 3;
+
+            // de arriba abajo: `below` cuenta los bloques sólidos seguidos desde la última superficie
+            let mut below = -1;
+            for y in (0..p.height).rev() {
+                let (fx, fy, fz) = (x as f32, y as f32, z as f32);
+                let bend = p.overhang * 8.0 * warp.fbm3(fx * 0.06, fy * 0.08, fz * 0.06, 3);
+                let solid = y == 0 || (y < p.height - 1 && (h - y) as f32 + bend > 0.0);
+                if !solid {
+                    below = -1;
+                    // el agua baja desde el nivel del mar hasta el primer sólido
+                    if y <= p.sea_level && (y == p.sea_level || world.get_block(x, y + 1, z) == Some(s.water)) {
+                        world.set_block(x, y, z, Some(s.water));
+                    }
+                    continue;
+                }
+                below += 1;
+                // espesor de tierra variable con ruido 3D: la piedra asoma en algunas laderas
+                let depth = 3.0 + 2.0 * soil.fbm3(fx * 0.15, fy * 0.15, fz * 0.15, 2);
+                let block = if y == 0 || below as f32 > depth {
+                    s.stone
+                } else if beach && below <= 2 {
+                    s.sand
+                } else if below == 0 && !deep && world.get_block(x, y + 1, z) != Some(s.water) {
+                    s.grass
+                } else {
+                    s.dirt
+                };
+                world.set_block(x, y, z, Some(block));
+            }
+        }
+    }
+    if p.caves { carve_caves(p, &s, &heights, &mut world); }
+    if p.ores { place_ores(p, reg, &s, &mut world); }
+    Ok(world)
+}
+
+/// Cavernas donde un fBm 3D de baja frecuencia supera un umbral ("queso") y
+/// túneles donde se cruzan las superficies de nivel cero de dos ruidos ("gusanos").
+fn carve_caves(p: &Params, s: &Strata, heights: &[i32], world: &mut World) {
+    let cheese = Perlin::new(p.seed ^ 0xc4ee_5e00);
+    let worm_a = Perlin::new(p.seed ^ 0x0a0a_1111);
+    let worm_b = Perlin::new(p.seed ^ 0x0b0b_2222);
+    let [sx, sz] = p.size;
+    for z in 0..sz {
+        for x in 0..sx {
+            let h = heights[(z * sx + x) as usize];
+            for y in 1..p.height {
+                let Some(b) = world.get_block(x, y, z) else { continue };
+                if b == s.water { continue; }
+                // bajo el agua se deja un techo para no vaciar el mar en la cueva
+                if h < p.sea_level + 2 && y >= h - 3 { continue; }
+                let (fx, fy, fz) = (x as f32, y as f32, z as f32);
+                let cave = cheese.fbm3(fx * 0.045, fy * 0.07, fz * 0.045, 2) > 0.28 || {
+                    let a = worm_a.noise3(fx * 0.05, fy * 0.07, fz * 0.05);
+                    let c = worm_b.noise3(fx * 0.05, fy * 0.07, fz * 0.05);
+                    a.abs() < 0.07 && c.abs() < 0.07
+                };
+                if cave {
+                    let fill = if y <= p.lava_level { s.lava } else { None };
+                    world.set_block(x, y, z, fill);
+                }
+            }
+        }
+    }
+}
+
+/// Sustituye piedra por mineral donde el ruido de cada mena supera su umbral.
+fn place_ores(p: &Params, reg: &BlockRegistry, s: &Strata, world: &mut World) {
+    let ores: Vec<(BlockId, Perlin, f32, i32)> = ORES.iter().enumerate()
+        .filter_map(|(i, &(name, thr, max_y))| Some((reg.find(name)?, Perlin::new(p.seed ^ (0x0e7e_0000 + i as u64)), thr, max_y)))
+        .collect();
+    let [sx, sz] = p.size;
+    for y in 1..p.height {
+        for z in 0..sz {
+            for x in 0..sx {
+                if world.get_block(x, y, z) != Some(s.stone) { continue; }
+                let (fx, fy, fz) = (x as f32 * 0.22, y as f32 * 0.22, z as f32 * 0.22);
+                if let Some(&(id, ..)) = ores.iter().find(|(_, n, thr, max_y)| y <= *max_y && n.noise3(fx, fy, fz) > *thr) {
+                    world.set_block(x, y, z, Some(id));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_fits_low_grids() {
+        let hills = Perlin::new(3);
+        for height in [0, 1, 2, 3, 32] {
+            let p = Params { height, ..Params::default() };
+            let h = p.surface(&hills, 5, 7);
+            assert!(h >= 1 && h <= (height - 2).max(1), "height {} -> {}", height, h);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::color::Color;
+use crate::png;
 use crate::ppm::load_ppm;
 
 // Tipos de textura (procedurales de respaldo)
@@ -6,12 +7,45 @@ use crate::ppm::load_ppm;
 pub enum TexKind {
     Stone, Wood, Metal, Water, Lava,
     GrassTop, GrassSide, Dirt, Cobble, Sand, Leaves, Glass,
+    Bark, LogTop, BirchBark, Torch,
 }
 
-// Dos modos: Procedural o Imagen cargada
+/// Siluetas recortadas para la geometría en cruz (pasto, flores).
+#[derive(Clone, Copy)]
+pub enum Plant { Tuft, Flower }
+
+impl TexKind {
+    /// Nombre en minúsculas usado por los archivos de escena.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "stone" => TexKind::Stone, "wood" => TexKind::Wood, "metal" => TexKind::Metal,
+            "water" => TexKind::Water, "lava" => TexKind::Lava,
+            "grass_top" => TexKind::GrassTop, "grass_side" => TexKind::GrassSide,
+            "dirt" => TexKind::Dirt, "cobble" => TexKind::Cobble, "sand" => TexKind::Sand,
+            "leaves" => TexKind::Leaves, "glass" => TexKind::Glass,
+            "bark" => TexKind::Bark, "log_top" => TexKind::LogTop, "birch_bark" => TexKind::BirchBark,
+            "torch" => TexKind::Torch,
+            _ => return None,
+        })
+    }
+}
+
+// Procedural, imagen cargada, tira animada o flujo sobre otra textura
 pub enum Texture {
     Procedural(TexKind),
+    /// Color liso (paletas de vóxeles, colores de MTL)
+    Solid(Color),
     Image { w: usize, h: usize, data: Vec<u8> },
+    /// Tira vertical de cuadros cuadrados (formato de Minecraft); `h` es el alto de un cuadro.
+    Animated { w: usize, h: usize, frames: usize, frame_time: f32, data: Vec<u8> },
+    /// Desplaza y ondula las UV de `inner` con el tiempo (agua, lava).
+    Flow { inner: Box<Texture>, speed: (f32, f32), warp: f32 },
+    /// Luminancia de `inner` por `gain`, para teñir por bioma.
+    Gray { inner: Box<Texture>, gain: f32 },
+    /// Manchas pixeladas de `color` sobre `inner` (menas en piedra).
+    Ore { inner: Box<Texture>, color: Color },
+    /// Silueta con agujeros: fuera de ella el rayo sigue de largo (ver `covers`).
+    Plant { kind: Plant, color: Color },
 }
 
 impl Texture {
@@ -25,22 +59,114 @@ impl Texture {
         }
     }
 
-    pub fn sample(&self, u: f32, v: f32) -> Color {
+    /// Imagen PNG o PPM según la extensión.
+    pub fn from_file(path: &str) -> Option<Self> {
+        if !path.to_ascii_lowercase().ends_with(".png") { return Self::from_ppm(path); }
+        let (w, h, data) = png::load(path).ok()?;
+        Some(Texture::Image { w, h, data })
+    }
+
+    /// Carga una tira de `alto/ancho` cuadros; `frame_time` en segundos por cuadro.
+    pub fn from_ppm_strip(path: &str, frame_time: f32) -> Option<Self> {
+        let img = load_ppm(path).ok()?;
+        if img.w == 0 || img.h < img.w || img.h % img.w != 0 { return None; }
+        let frames = img.h / img.w;
+        Some(Texture::Animated { w: img.w, h: img.w, frames, frame_time, data: img.data })
+    }
+
+    pub fn flow(inner: Texture, speed: (f32, f32), warp: f32) -> Self {
+        Texture::Flow { inner: Box::new(inner), speed, warp }
+    }
+
+    pub fn grayscale(inner: Texture, gain: f32) -> Self {
+        Texture::Gray { inner: Box::new(inner), gain }
+    }
+
+    pub fn ore(inner: Texture, color: Color) -> Self {
+        Texture::Ore { inner: Box::new(inner), color }
+    }
+
+    /// Falso en los texeles transparentes de las siluetas; las demás texturas son opacas.
+    pub fn covers(&self, u: f32, v: f32) -> bool {
+        let Texture::Plant { kind, .. } = self else { return true };
+        let (uu, vv) = (((u % 1.0) + 1.0) % 1.0, ((v % 1.0) + 1.0) % 1.0);
+        let (px, py) = ((uu * 16.0).floor(), (vv * 16.0).floor());
+        match kind {
+            // hojas de pasto: cada columna impar con su propia altura
+            Plant::Tuft => px as i32 % 2 == 1 && py < 4.0 + 11.0 * hash12(px, 3.7),
+            // tallo centrado y corola redonda
+            Plant::Flower => {
+                let stem = (7.0..9.0).contains(&px) && py < 9.0;
+                let leaf = py < 4.0 && (px - 7.5).abs() < 3.0 && (px - 7.5).abs() > 1.0 && py > 1.0;
+                stem || leaf || (px - 7.5).hypot(py - 10.5) < 3.2
+            }
+        }
+    }
+
+    /// `v` crece hacia arriba; la fila 0 de la imagen es el borde superior.
+    /// `time` es el tiempo de animación en segundos.
+    pub fn sample(&self, u: f32, v: f32, time: f32) -> Color {
         // wrap [0,1)
         let uu = ((u % 1.0) + 1.0) % 1.0;
         let vv = ((v % 1.0) + 1.0) % 1.0;
         match self {
-            Texture::Procedural(kind) => sample_procedural(kind, uu, vv),
-            Texture::Image { w, h, data } => {
-                let x = (uu * (*w as f32)) as usize % *w;
-                let y = (vv * (*h as f32)) as usize % *h;
-                let idx = (y * (*w) + x) * 3;
-                Color::from_u8(data[idx], data[idx + 1], data[idx + 2])
+            Texture::Procedural(kind) => sample_procedural(kind, uu, vv, time),
+            Texture::Solid(c) => *c,
+            Texture::Image { w, h, data } => texel(data, *w, *h, 0, uu, vv),
+            Texture::Animated { w, h, frames, frame_time, data } => {
+                // mezcla lineal entre cuadros consecutivos para que no salte
+                let f = (time / frame_time.max(1e-4)).max(0.0);
+                let i = f.floor() as usize;
+                let k = f - f.floor();
+                let a = texel(data, *w, *h, i % frames, uu, vv);
+                let b = texel(data, *w, *h, (i + 1) % frames, uu, vv);
+                Color::lerp(a, b, k)
+            }
+            Texture::Flow { inner, speed, warp } => {
+                let tau = std::f32::consts::TAU;
+                let du = speed.0 * time + warp * (tau * (vv + time * 0.25)).sin();
+                let dv = speed.1 * time + warp * (tau * (uu - time * 0.2)).cos();
+                inner.sample(uu + du, vv + dv, time)
+            }
+            Texture::Gray { inner, gain } => {
+                let c = inner.sample(uu, vv, time);
+                Color::splat((0.2126 * c.r + 0.7152 * c.g + 0.0722 * c.b) * gain)
+            }
+            Texture::Ore { inner, color } => {
+                // grumos de ruido en una rejilla de 16×16 con borde oscuro
+                let (px, py) = ((uu * 16.0).floor(), (vv * 16.0).floor());
+                let n = noise2(px * 0.3 + 3.1, py * 0.3 + 7.3);
+                let base = inner.sample(uu, vv, time);
+                if n > 0.66 {
+                    color.mul(0.8 + 0.4 * hash12(px, py))
+                } else if n > 0.6 {
+                    base.mul(0.55)
+                } else {
+                    base
+                }
+            }
+            Texture::Plant { kind, color } => {
+                let (px, py) = ((uu * 16.0).floor(), (vv * 16.0).floor());
+                let shade = 0.8 + 0.3 * hash12(px, py);
+                match kind {
+                    // gris claro: el verde lo pone el tinte del bioma
+                    Plant::Tuft => Color::splat((0.5 + 0.4 * vv) * shade),
+                    Plant::Flower if py < 9.0 => Color::new(0.25, 0.55, 0.18).mul(shade),
+                    Plant::Flower if (px - 7.5).hypot(py - 10.5) < 1.2 => Color::new(0.95, 0.85, 0.3),
+                    Plant::Flower => color.mul(shade),
+                }
             }
         }
     }
 }
 
+fn texel(data: &[u8], w: usize, h: usize, frame: usize, u: f32, v: f32) -> Color {
+    let x = (u * (w as f32)) as usize % w;
+    let y = (((1.0 - v) * (h as f32)) as usize).min(h - 1);
+    let idx = ((frame * h + y) * w + x) * 3;
+    Color::from_u8(data[idx], data[idx + 1], data[idx + 2])
+}
+
 /* =======================
    Texturas procedurales
    ======================= */
@@ -48,7 +174,7 @@ impl Texture {
 fn fract(x: f32) -> f32 { x - x.floor() }
 
 fn hash12(x: f32, y: f32) -> f32 {
-    let v = (x * 12.9898 + y * 78.233).sin() * 43758.5453;
+    let v = (x * 12.9898 + y * 78.233).sin() * 43758.547;
     fract(v)
 }
 
@@ -79,7 +205,7 @@ fn checker(u: f32, v: f32, scale: f32, c0: Color, c1: Color) -> Color {
     if (uu + vv) & 1 == 0 { c0 } else { c1 }
 }
 
-fn sample_procedural(kind: &TexKind, u: f32, v: f32) -> Color {
+fn sample_procedural(kind: &TexKind, u: f32, v: f32, time: f32) -> Color {
     match kind {
         // Piedra: gris moteado con fBm
         TexKind::Stone => {
@@ -95,16 +221,17 @@ fn sample_procedural(kind: &TexKind, u: f32, v: f32) -> Color {
         // Metal: bandas sutiles
         TexKind::Metal => {
             let band = ((u * 64.0).sin() * 0.5 + 0.5) * 0.25 + 0.65;
-            Color::splat(band as f32)
+            Color::splat(band)
         }
-        // Agua: ondulaciones
+        // Agua: ondulaciones que se desplazan
         TexKind::Water => {
-            let w = 0.7 + 0.3 * ((u * 10.0).sin() * (v * 12.0).cos());
-            Color::new(0.15, 0.35, 0.8).mul(w as f32)
+            let w = 0.7 + 0.3 * ((u * 10.0 + time * 2.0).sin() * (v * 12.0 - time * 1.5).cos());
+            Color::new(0.15, 0.35, 0.8).mul(w)
         }
-        // Lava: incandescencia con vetas
+        // Lava: incandescencia con vetas que se revuelven lentamente
         TexKind::Lava => {
-            let t = ((u * 20.0).sin() + (v * 24.0).cos()) * 0.5 + 0.5;
+            let f = fbm(u * 4.0 + time * 0.3, v * 4.0 - time * 0.2, 3);
+            let t = ((u * 20.0 + f * 4.0).sin() + (v * 24.0 - time * 0.8).cos()) * 0.5 + 0.5;
             Color::new(0.95, 0.35 + 0.5 * t, 0.08)
         }
         // Césped superior
@@ -117,8 +244,8 @@ fn sample_procedural(kind: &TexKind, u: f32, v: f32) -> Color {
             let f = fbm(u * 8.0, v * 8.0, 3);
             let top = Color::new(0.14 + 0.2 * f, 0.46 + 0.35 * f, 0.12 + 0.15 * f);
             let dirt = Color::new(0.40, 0.30, 0.18);
-            let edge = ((v * 1.0) - 0.5) * 8.0;
-            let t = (edge.clamp(-1.0, 1.0) * 0.5 + 0.5);
+            let edge = (0.5 - v) * 8.0;
+            let t = edge.clamp(-1.0, 1.0) * 0.5 + 0.5;
             top.mul(1.0 - t).add(dirt.mul(t))
         }
         TexKind::Dirt => {
@@ -141,7 +268,55 @@ fn sample_procedural(kind: &TexKind, u: f32, v: f32) -> Color {
         }
         TexKind::Glass => {
             let f = 0.85 + 0.1 * ((u * 20.0).sin() * (v * 20.0).cos());
-            Color::new(0.75, 0.9, 1.0).mul(f as f32)
+            Color::new(0.75, 0.9, 1.0).mul(f)
+        }
+        // Corteza: vetas verticales oscuras
+        TexKind::Bark => {
+            let f = fbm(u * 12.0, v * 2.0, 3);
+            let stripe = ((u * 6.0 + f * 2.0).sin() * 0.5 + 0.5) * 0.25;
+            Color::new(0.36 - stripe * 0.5, 0.27 - stripe * 0.4, 0.15 - stripe * 0.2)
+        }
+        // Corteza de abedul: blanca con marcas negras horizontales
+        TexKind::BirchBark => {
+            let (px, py) = ((u * 16.0).floor(), (v * 16.0).floor());
+            let mark = hash12(py, 1.3) > 0.7 && hash12((px / 4.0).floor(), py) > 0.35;
+            if mark { Color::new(0.18, 0.17, 0.15) } else { Color::new(0.86, 0.85, 0.8).mul(0.9 + 0.1 * hash12(px, py)) }
+        }
+        // Antorcha: palo de madera y llama en la mitad de arriba (UV del bloque)
+        TexKind::Torch => {
+            if v < 0.5 {
+                Color::new(0.42, 0.3, 0.16).mul(0.85 + 0.15 * hash12((v * 16.0).floor(), 2.7))
+            } else {
+                let hot = ((v - 0.5) * 4.0).min(1.0);
+                Color::new(1.0, 0.85 - 0.3 * hot, 0.4 - 0.3 * hot)
+            }
+        }
+        // Tapa del tronco: anillos concéntricos
+        TexKind::LogTop => {
+            let r = (u - 0.5).hypot(v - 0.5);
+            if r > 0.45 {
+                Color::new(0.30, 0.22, 0.12)
+            } else {
+                let ring = (r * 40.0 + fbm(u * 6.0, v * 6.0, 2) * 3.0).sin() * 0.5 + 0.5;
+                Color::new(0.62 + 0.1 * ring, 0.48 + 0.08 * ring, 0.28 + 0.05 * ring)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_rejects_bad_sizes() {
+        let dir = std::env::temp_dir();
+        for (name, w, h) in [("tira_0x16.ppm", 0, 16), ("tira_4x6.ppm", 4, 6), ("tira_4x2.ppm", 4, 2)] {
+            let path = dir.join(name);
+            let mut ppm = format!("P6\n{} {}\n255\n", w, h).into_bytes();
+            ppm.resize(ppm.len() + w * h * 3, 0);
+            std::fs::write(&path, ppm).unwrap();
+            assert!(Texture::from_ppm_strip(path.to_str().unwrap(), 0.1).is_none(), "{}x{}", w, h);
         }
     }
 }
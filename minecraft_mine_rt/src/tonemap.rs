@@ -0,0 +1,35 @@
+use crate::color::Color;
+
+// Mapeo de tonos: de color lineal sin límite (emisivos, sol) a [0, 1] para las
+// salidas de 8 bits. Va aparte del trazado, sobre el `Framebuffer` ya promediado.
+
+#[derive(Clone, Copy, Default)]
+pub enum ToneMap {
+    /// Recorta a [0, 1]: lo que se veía siempre
+    #[default]
+    Clamp,
+    /// x / (1 + x) por canal: nunca satura, aplana los medios tonos
+    Reinhard,
+    /// Curva fílmica ACES (ajuste de Narkowicz): contraste en los medios y hombro suave
+    Aces,
+}
+
+impl ToneMap {
+    pub fn by_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "clamp" => ToneMap::Clamp,
+            "reinhard" => ToneMap::Reinhard,
+            "aces" => ToneMap::Aces,
+            _ => return None,
+        })
+    }
+
+    pub fn apply(self, c: Color) -> Color {
+        let curve = |f: fn(f32) -> f32| Color::new(f(c.r.max(0.0)), f(c.g.max(0.0)), f(c.b.max(0.0)));
+        match self {
+            ToneMap::Clamp => c,
+            ToneMap::Reinhard => curve(|x| x / (1.0 + x)),
+            ToneMap::Aces => curve(|x| (x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)),
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::f32::consts::{SQRT_2, TAU};
+
+use crate::biome::Biome;
+use crate::block::{BlockId, BlockRegistry};
+use crate::math::Vec3;
+use crate::rng::Rng;
+use crate::world::World;
+
+/// Especies de árbol del generador.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Species {
+    /// Tronco corto y copa en bola
+    Oak,
+    /// Como el roble pero más alto y con corteza blanca
+    Birch,
+    /// Cono de capas alternas
+    Spruce,
+    /// Ramas de un sistema L con racimos de hojas en las puntas
+    BigOak,
+}
+
+impl Species {
+    pub fn by_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "oak" => Species::Oak,
+            "birch" => Species::Birch,
+            "spruce" => Species::Spruce,
+            "big_oak" => Species::BigOak,
+            _ => return None,
+        })
+    }
+}
+
+/// Parámetros de la vegetación sobre un terreno generado.
+#[derive(Clone)]
+pub struct Params {
+    pub seed: u64,
+    /// Distancia mínima entre troncos (muestreo de disco de Poisson)
+    pub spacing: f32,
+    /// Especies posibles, con la misma probabilidad; vacío = sin árboles
+    pub species: Vec<Species>,
+    /// Probabilidad de pasto y de flor sobre cada bloque de césped libre
+    pub grass: f32,
+    pub flowers: f32,
+}
+
+impl Params {
+    /// Mezcla típica de cada bioma según su clima.
+    pub fn for_biome(seed: u64, b: Biome) -> Self {
+        use Species::*;
+        let (spacing, species, grass, flowers) = if b.temperature < 0.4 {
+            (4.5, vec![Spruce], 0.15, 0.0) // taiga
+        } else if b.humidity < 0.2 {
+            (12.0, vec![Oak], 0.5, 0.0) // sabana
+        } else if b.temperature > 0.9 && b.humidity > 0.8 {
+            (5.0, vec![BigOak, Oak], 0.45, 0.02) // jungla
+        } else if b.humidity >= 0.7 {
+            (5.0, vec![Oak, Oak, Birch, BigOak], 0.25, 0.04) // bosque, pantano
+        } else {
+            (10.0, vec![Oak, Oak, Birch], 0.35, 0.06) // llanura
+        };
+        Self { seed, spacing, species, grass, flowers }
+    }
+}
+
+/// Bloques de la vegetación; sin abedul o abeto se usan el tronco y las hojas comunes.
+struct Blocks {
+    grass: BlockId,
+    log: BlockId,
+    leaves: BlockId,
+    birch: (BlockId, BlockId),
+    spruce: (BlockId, BlockId),
+    tuft: Option<BlockId>,
+    flowers: Vec<BlockId>,
+}
+
+impl Blocks {
+    fn from(reg: &BlockRegistry) -> Result<Self, String> {
+        let id = |name: &str| reg.find(name).ok_or_else(|| format!("la vegetación necesita el bloque '{}'", name));
+        let (log, leaves) = (id("log")?, id("leaves")?);
+        let pair = |l: &str, f: &str| (reg.find(l).unwrap_or(log), reg.find(f).unwrap_or(leaves));
+        Ok(Self {
+            grass: id("grass")?,
+            log,
+            leaves,
+            birch: pair("birch_log", "birch_leaves"),
+            spruce: pair("spruce_log", "spruce_leaves"),
+            tuft: reg.find("short_grass"),
+            flowers: ["poppy", "dandelion", "cornflower"].iter().filter_map(|f| reg.find(f)).collect(),
+        })
+    }
+}
+
+/// Planta árboles en puntos de un disco de Poisson que caen sobre césped,
+/// y después pasto y flores en los bloques de césped que quedan libres.
+pub fn plant(world: &mut World, reg: &BlockRegistry, p: &Params) -> Result<(), String> {
+    let b = Blocks::from(reg)?;
+    let mut rng = Rng::new(p.seed ^ 0x7ee5_5eed);
+    let [sx, _, sz] = world.size();
+
+    if !p.species.is_empty() {
+        for (fx, fz) in poisson_disk(sx as f32, sz as f32, p.spacing.max(1.0), &mut rng) {
+            let (x, z) = (fx as i32, fz as i32);
+            let Some(y) = top(world, x, z) else { continue };
+            if world.get_block(x, y, z) != Some(b.grass) || !clear(world, &b, x, y, z) { continue; }
+            let species = p.species[rng.below(p.species.len())];
+            world.set_block(x, y, z, reg.find("dirt").or(Some(b.grass)));
+            let mut t = Tree { world, rng: &mut rng };
+            match species {
+                Species::Oak => t.blob(&b, (b.log, b.leaves), x, y + 1, z, 4),
+                Species::Birch => t.blob(&b, b.birch, x, y + 1, z, 5),
+                Species::Spruce => t.spruce(b.spruce, x, y + 1, z),
+                Species::BigOak => t.big_oak(&b, x, y + 1, z),
+            }
+        }
+    }
+
+    for z in 0..sz {
+        for x in 0..sx {
+            let Some(y) = top(world, x, z) else { continue };
+            if world.get_block(x, y, z) != Some(b.grass) { continue; }
+            let r = rng.f32();
+            let plant = if r < p.grass {
+                b.tuft
+            } else if r < p.grass + p.flowers && !b.flowers.is_empty() {
+                Some(b.flowers[rng.below(b.flowers.len())])
+            } else {
+                None
+            };
+            if plant.is_some() { world.set_block(x, y + 1, z, plant); }
+        }
+    }
+    Ok(())
+}
+
+/// Bloque sólido más alto de la columna.
+fn top(world: &World, x: i32, z: i32) -> Option<i32> {
+    (0..world.size()[1]).rev().find(|&y| world.get_block(x, y, z).is_some())
+}
+
+/// Hueco libre para la copa: nada más que aire u hojas en 5 × 6 × 5 sobre el suelo,
+/// para no meter árboles bajo salientes ni hojas dentro de las casas.
+fn clear(world: &World, b: &Blocks, x: i32, y: i32, z: i32) -> bool {
+    (y + 1..=y + 6).all(|cy| (z - 2..=z + 2).all(|cz| (x - 2..=x + 2).all(|cx| {
+        world.get_block(cx, cy, cz).is_none_or(|c| c == b.leaves || c == b.birch.1 || c == b.spruce.1)
+    })))
+}
+
+/// Muestreo de Bridson: puntos en [0, w) × [0, h) separados al menos `r`; ninguno si el área está vacía.
+fn poisson_disk(w: f32, h: f32, r: f32, rng: &mut Rng) -> Vec<(f32, f32)> {
+    if w <= 0.0 || h <= 0.0 { return Vec::new(); }
+    let cell = r / SQRT_2;
+    let (gw, gh) = ((w / cell).ceil() as i32, (h / cell).ceil() as i32);
+    let mut grid: Vec<Option<usize>> = vec![None; (gw * gh) as usize];
+    let slot = |x: f32, z: f32| ((z / cell) as i32 * gw + (x / cell) as i32) as usize;
+    let mut pts = vec![(rng.range(0.0, w), rng.range(0.0, h))];
+    grid[slot(pts[0].0, pts[0].1)] = Some(0);
+    let mut active = vec![0];
+    while !active.is_empty() {
+        let k = rng.below(active.len());
+        let (px, pz) = pts[active[k]];
+        let mut found = false;
+        for _ in 0..30 {
+            let (a, d) = (rng.range(0.0, TAU), rng.range(r, 2.0 * r));
+            let (x, z) = (px + d * a.cos(), pz + d * a.sin());
+            if x < 0.0 || z < 0.0 || x >= w || z >= h { continue; }
+            let (gx, gz) = ((x / cell) as i32, (z / cell) as i32);
+            let near = (gz - 2..=gz + 2).any(|j| (gx - 2..=gx + 2).any(|i| {
+                i >= 0 && j >= 0 && i < gw && j < gh
+                    && grid[(j * gw + i) as usize].is_some_and(|q| (pts[q].0 - x).hypot(pts[q].1 - z) < r)
+            }));
+            if near { continue; }
+            grid[slot(x, z)] = Some(pts.len());
+            active.push(pts.len());
+            pts.push((x, z));
+            found = true;
+            break;
+        }
+        if !found { active.swap_remove(k); }
+    }
+    pts
+}
+
+struct Tree<'a> {
+    world: &'a mut World,
+    rng: &'a mut Rng,
+}
+
+impl Tree<'_> {
+    /// Las hojas solo ocupan aire; los troncos también pueden pisar hojas.
+    fn leaf(&mut self, x: i32, y: i32, z: i32, id: BlockId) {
+        if self.world.get_block(x, y, z).is_none() { self.world.set_block(x, y, z, Some(id)); }
+    }
+
+    fn log(&mut self, b: &Blocks, x: i32, y: i32, z: i32, id: BlockId) {
+        let cur = self.world.get_block(x, y, z);
+        if cur.is_none_or(|c| c == b.leaves || c == b.birch.1 || c == b.spruce.1) {
+            self.world.set_block(x, y, z, Some(id));
+        }
+    }
+
+    /// Roble o abedul: tronco de `min_h`..`min_h + 2` y copa de cuatro capas
+    /// (dos de radio 2 y dos de radio 1) con esquinas al azar.
+    fn blob(&mut self, b: &Blocks, (log, leaves): (BlockId, BlockId), x: i32, y: i32, z: i32, min_h: i32) {
+        let h = min_h + self.rng.below(3) as i32;
+        for ly in y + h - 3..=y + h {
+            let r: i32 = if ly < y + h - 1 { 2 } else { 1 };
+            for dz in -r..=r {
+                for dx in -r..=r {
+                    let corner = dx.abs() == r && dz.abs() == r;
+                    if corner && (ly == y + h || self.rng.f32() < 0.5) { continue; }
+                    self.leaf(x + dx, ly, z + dz, leaves);
+                }
+            }
+        }
+        for ly in y..y + h { self.log(b, x, ly, z, log); }
+    }
+
+    /// Abeto: capas de radio alterno que crecen hacia abajo, con la punta sobre el tronco.
+    fn spruce(&mut self, (log, leaves): (BlockId, BlockId), x: i32, y: i32, z: i32) {
+        let h = 6 + self.rng.below(4) as i32;
+        let top = y + h;
+        self.leaf(x, top, z, leaves);
+        for ly in (y + 2..top).rev() {
+            let k = top - ly;
+            let r = ((k + 1) / 2).min(3) - (k % 2 == 0) as i32;
+            for dz in -r..=r {
+                for dx in -r..=r {
+                    if dx.abs() + dz.abs() <= r + r / 2 { self.leaf(x + dx, ly, z + dz, leaves); }
+                }
+            }
+        }
+        for ly in y..top { self.world.set_block(x, ly, z, Some(log)); }
+    }
+
+    /// Roble grande: la cadena de un sistema L interpretada por una tortuga 3D.
+    /// `F` avanza dejando tronco, `&` inclina, `/` gira sobre el propio eje,
+    /// `[` `]` abren y cierran rama (más corta) y `A` deja un racimo de hojas.
+    fn big_oak(&mut self, b: &Blocks, x: i32, y: i32, z: i32) {
+        let mut s = String::from("FFA");
+        for _ in 0..3 { s = s.replace('A', "F[&FA]///[&FA]///[&FA]"); }
+
+        let base = Vec3::new(x as f32 + 0.5, y as f32, z as f32 + 0.5);
+        let spin = self.rng.range(0.0, TAU);
+        let mut turtle = Turtle {
+            pos: base,
+            h: Vec3::new(0.0, 1.0, 0.0),
+            l: Vec3::new(spin.cos(), 0.0, spin.sin()),
+            u: Vec3::new(-spin.sin(), 0.0, spin.cos()),
+            len: 2.2,
+        };
+        let mut stack = Vec::new();
+        for c in s.chars() {
+            match c {
+                'F' => {
+                    let end = turtle.pos.add(turtle.h.mul(turtle.len));
+                    let steps = (turtle.len * 4.0).ceil() as i32;
+                    for i in 0..=steps {
+                        let p = turtle.pos.add(end.sub(turtle.pos).mul(i as f32 / steps as f32));
+                        self.log(b, p.x.floor() as i32, p.y.floor() as i32, p.z.floor() as i32, b.log);
+                    }
+                    turtle.pos = end;
+                }
+                '&' => {
+                    let a = self.rng.range(25.0, 45.0).to_radians();
+                    turtle.h = rotate(turtle.h, turtle.l, a);
+                    turtle.u = rotate(turtle.u, turtle.l, a);
+                }
+                '/' => {
+                    let a = self.rng.range(30.0, 50.0).to_radians();
+                    turtle.l = rotate(turtle.l, turtle.h, a);
+                    turtle.u = rotate(turtle.u, turtle.h, a);
+                }
+                '[' => {
+                    stack.push(turtle);
+                    turtle.len *= 0.72;
+                }
+                ']' => turtle = stack.pop().unwrap_or(turtle),
+                'A' => {
+                    let r = self.rng.range(1.6, 2.4);
+                    let ri = r.ceil() as i32;
+                    let (cx, cy, cz) = (turtle.pos.x.floor() as i32, turtle.pos.y.floor() as i32, turtle.pos.z.floor() as i32);
+                    for dy in -ri..=ri {
+                        for dz in -ri..=ri {
+                            for dx in -ri..=ri {
+                                if ((dx * dx + dy * dy * 2 + dz * dz) as f32).sqrt() <= r {
+                                    self.leaf(cx + dx, cy + dy, cz + dz, b.leaves);
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Estado de la tortuga: posición, rumbo (h), izquierda (l), arriba (u) y largo del paso.
+#[derive(Clone, Copy)]
+struct Turtle {
+    pos: Vec3,
+    h: Vec3,
+    l: Vec3,
+    u: Vec3,
+    len: f32,
+}
+
+/// Rota `v` un ángulo `a` alrededor del eje unitario `k` (Rodrigues).
+fn rotate(v: Vec3, k: Vec3, a: f32) -> Vec3 {
+    let (s, c) = a.sin_cos();
+    v.mul(c).add(k.cross(v).mul(s)).add(k.mul(k.dot(v) * (1.0 - c)))
+}
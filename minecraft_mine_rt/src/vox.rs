@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+
+use crate::aabb::Aabb;
+use crate::block::{BlockRegistry, Faces};
+use crate::color::Color;
+use crate::material::Material;
+use crate::math::Vec3;
+use crate::texture::Texture;
+
+/// Vóxel ya transformado a coordenadas del mundo de MagicaVoxel (z arriba).
+pub struct Voxel {
+    pub pos: [i32; 3],
+    pub color: u8,
+}
+
+/// Contenido de un `.vox`: todos los modelos colocados según su nodo nTRN.
+pub struct VoxScene {
+    pub voxels: Vec<Voxel>,
+    /// RGBA por índice de color; el índice 0 es vacío.
+    pub palette: [[u8; 4]; 256],
+    /// Propiedades MATL por índice de color (`_type`, `_metal`, `_rough`…)
+    pub materials: HashMap<u8, HashMap<String, String>>,
+}
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+pub fn load(path: &str) -> io::Result<VoxScene> {
+    parse(&fs::read(path)?)
+}
+
+/* =======================
+   Lectura de chunks RIFF
+   ======================= */
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.data.len()).ok_or_else(|| bad("vox truncado"))?;
+        let b = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(b)
+    }
+    fn i32(&mut self) -> io::Result<i32> {
+        Ok(i32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+    fn len(&mut self) -> io::Result<usize> {
+        usize::try_from(self.i32()?).map_err(|_| bad("longitud negativa en vox"))
+    }
+    fn string(&mut self) -> io::Result<String> {
+        let n = self.len()?;
+        Ok(String::from_utf8_lossy(self.bytes(n)?).into_owned())
+    }
+    fn dict(&mut self) -> io::Result<HashMap<String, String>> {
+        let n = self.len()?;
+        let mut d = HashMap::new();
+        for _ in 0..n {
+            let k = self.string()?;
+            d.insert(k, self.string()?);
+        }
+        Ok(d)
+    }
+    fn done(&self) -> bool { self.pos >= self.data.len() }
+    /// Cuántos registros de `size` bytes caben como mucho en lo que queda: tope
+    /// para reservar memoria según una cuenta leída del archivo.
+    fn fits(&self, size: usize) -> usize { (self.data.len() - self.pos) / size }
+}
+
+struct Model {
+    size: [i32; 3],
+    voxels: Vec<([i32; 3], u8)>,
+}
+
+/// Rotación entera 3x3 y traslación.
+#[derive(Clone, Copy)]
+struct Xform {
+    r: [[i32; 3]; 3],
+    t: [i32; 3],
+}
+
+impl Xform {
+    const IDENTITY: Xform = Xform { r: [[1, 0, 0], [0, 1, 0], [0, 0, 1]], t: [0, 0, 0] };
+
+    fn apply(&self, p: [i32; 3]) -> [i32; 3] {
+        let mut out = self.t;
+        for (i, row) in self.r.iter().enumerate() {
+            out[i] += row[0] * p[0] + row[1] * p[1] + row[2] * p[2];
+        }
+        out
+    }
+
+    /// `self` después de `local` (padre ∘ hijo).
+    fn then(&self, local: &Xform) -> Xform {
+        let mut r = [[0; 3]; 3];
+        for (i, row) in r.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = (0..3).map(|k| self.r[i][k] * local.r[k][j]).sum();
+            }
+        }
+        Xform { r, t: self.apply(local.t) }
+    }
+
+    /// `_r` empaqueta la rotación: bits 0-1 y 2-3 columna no nula de las filas 0 y 1,
+    /// bits 4-6 signo negativo de cada fila.
+    fn from_attrs(attrs: &HashMap<String, String>) -> Xform {
+        let mut x = Xform::IDENTITY;
+        if let Some(bits) = attrs.get("_r").and_then(|s| s.trim().parse::<u8>().ok()) {
+            let c0 = (bits & 3) as usize;
+            let c1 = ((bits >> 2) & 3) as usize;
+            // la tercera fila usa la columna que queda; con c0 o c1 = 3 no hay ninguna
+            if c0 < 3 && c1 < 3 && c0 != c1 {
+                x.r = [[0; 3]; 3];
+                for (row, col) in [c0, c1, 3 - c0 - c1].into_iter().enumerate() {
+                    x.r[row][col] = if bits & (1 << (4 + row)) != 0 { -1 } else { 1 };
+                }
+            } else {
+                eprintln!("aviso: rotación vox inválida (_r = {}), se usa la identidad", bits);
+            }
+        }
+        if let Some(t) = attrs.get("_t") {
+            for (i, v) in t.split_whitespace().take(3).enumerate() {
+                x.t[i] = v.parse().unwrap_or(0);
+            }
+        }
+        x
+    }
+}
+
+enum Node {
+    Transform { xform: Xform, child: i32 },
+    Group { children: Vec<i32> },
+    Shape { models: Vec<usize> },
+}
+
+pub fn parse(data: &[u8]) -> io::Result<VoxScene> {
+    let mut r = Reader { data, pos: 0 };
+    if r.bytes(4)? != b"VOX " { return Err(bad("no es un archivo .vox")); }
+    let _version = r.i32()?;
+    if r.bytes(4)? != b"MAIN" { return Err(bad("falta el chunk MAIN")); }
+    let main_content = r.len()?;
+    let _main_children = r.len()?;
+    r.bytes(main_content)?;
+
+    let mut models: Vec<Model> = Vec::new();
+    let mut size = [0; 3];
+    let mut palette = default_palette();
+    let mut nodes: HashMap<i32, Node> = HashMap::new();
+    let mut materials = HashMap::new();
+
+    while !r.done() {
+        let id: [u8; 4] = r.bytes(4)?.try_into().unwrap();
+        let content = r.len()?;
+        let children = r.len()?;
+        let mut c = Reader { data: r.bytes(content)?, pos: 0 };
+        r.bytes(children)?;
+        match &id {
+            b"SIZE" => size = [c.i32()?, c.i32()?, c.i32()?],
+            b"XYZI" => {
+                let n = c.len()?;
+                let mut voxels = Vec::with_capacity(n.min(c.fits(4)));
+                for _ in 0..n {
+                    let v = c.bytes(4)?;
+                    voxels.push(([v[0] as i32, v[1] as i32, v[2] as i32], v[3]));
+                }
+                models.push(Model { size, voxels });
+            }
+            // la entrada i del chunk corresponde al índice de color i+1
+            b"RGBA" => {
+                for i in 0..255 {
+                    palette[i + 1] = c.bytes(4)?.try_into().unwrap();
+                }
+            }
+            b"nTRN" => {
+                let node = c.i32()?;
+                let _attrs = c.dict()?;
+                let child = c.i32()?;
+                let _reserved = c.i32()?;
+                let _layer = c.i32()?;
+                let frames = c.len()?;
+                // solo el primer cuadro de la animación
+                let mut xform = Xform::IDENTITY;
+                for f in 0..frames {
+                    let attrs = c.dict()?;
+                    if f == 0 { xform = Xform::from_attrs(&attrs); }
+                }
+                nodes.insert(node, Node::Transform { xform, child });
+            }
+            b"nGRP" => {
+                let node = c.i32()?;
+                let _attrs = c.dict()?;
+                let n = c.len()?;
+                let children = (0..n).map(|_| c.i32()).collect::<io::Result<_>>()?;
+                nodes.insert(node, Node::Group { children });
+            }
+            b"nSHP" => {
+                let node = c.i32()?;
+                let _attrs = c.dict()?;
+                let n = c.len()?;
+                // id del modelo y tamaño del diccionario: 8 bytes por modelo
+                let mut shape_models = Vec::with_capacity(n.min(c.fits(8)));
+                for _ in 0..n {
+                    shape_models.push(c.len()?);
+                    let _model_attrs = c.dict()?;
+                }
+                nodes.insert(node, Node::Shape { models: shape_models });
+            }
+            b"MATL" => {
+                let idx = c.i32()?;
+                let props = c.dict()?;
+                if (1..256).contains(&idx) { materials.insert(idx as u8, props); }
+            }
+            // PACK, LAYR, rOBJ, rCAM, NOTE, IMAP… no afectan la geometría
+            _ => {}
+        }
+    }
+
+    let mut voxels = Vec::new();
+    let mut place = |m: &Model, x: &Xform| {
+        // MagicaVoxel gira y traslada cada modelo alrededor de su centro
+        let pivot = [m.size[0] / 2, m.size[1] / 2, m.size[2] / 2];
+        for (p, color) in &m.voxels {
+            let local = [p[0] - pivot[0], p[1] - pivot[1], p[2] - pivot[2]];
+            voxels.push(Voxel { pos: x.apply(local), color: *color });
+        }
+    };
+
+    if nodes.contains_key(&0) {
+        // recorrido del grafo de escena desde la raíz (nodo 0); es un árbol, así que
+        // un nodo que aparece dos veces es un ciclo o un archivo dañado
+        let mut stack = vec![(0, Xform::IDENTITY, 0)];
+        let mut seen = HashSet::new();
+        while let Some((id, xform, depth)) = stack.pop() {
+            if depth > 64 { return Err(bad("grafo de escena vox demasiado profundo")); }
+            if !seen.insert(id) { return Err(bad("nodo vox repetido en el grafo de escena")); }
+            match nodes.get(&id) {
+                Some(Node::Transform { xform: local, child }) => stack.push((*child, xform.then(local), depth + 1)),
+                Some(Node::Group { children }) => {
+                    stack.extend(children.iter().map(|c| (*c, xform, depth + 1)));
+                }
+                Some(Node::Shape { models: ids }) => {
+                    for &i in ids {
+                        let m = models.get(i).ok_or_else(|| bad("nSHP apunta a un modelo inexistente"))?;
+                        place(m, &xform);
+                    }
+                }
+                None => return Err(bad("nodo vox inexistente")),
+            }
+        }
+    } else {
+        // archivos viejos sin grafo: modelos en el origen, sin centrar
+        for m in &models {
+            voxels.extend(m.voxels.iter().map(|(p, c)| Voxel { pos: *p, color: *c }));
+        }
+    }
+
+    Ok(VoxScene { voxels, palette, materials })
+}
+
+/// Paleta por defecto de MagicaVoxel: cubo de 6x6x6 niveles (sin el negro)
+/// seguido de rampas de rojo, verde, azul y gris.
+fn default_palette() -> [[u8; 4]; 256] {
+    let mut p = [[0u8; 4]; 256];
+    let levels = [0xff, 0xcc, 0x99, 0x66, 0x33, 0x00];
+    let mut i = 1;
+    for r in levels {
+        for g in levels {
+            for b in levels {
+                if r == 0 && g == 0 && b == 0 { continue; }
+                p[i] = [r, g, b, 0xff];
+                i += 1;
+            }
+        }
+    }
+    let ramp = [0xee, 0xdd, 0xbb, 0xaa, 0x88, 0x77, 0x55, 0x44, 0x22, 0x11];
+    for k in 0..4 {
+        for v in ramp {
+            p[i] = match k { 0 => [v, 0, 0, 0xff], 1 => [0, v, 0, 0xff], 2 => [0, 0, v, 0xff], _ => [v, v, v, 0xff] };
+            i += 1;
+        }
+    }
+    p
+}
+
+impl VoxScene {
+    /// Cubos unitarios con la esquina mínima del modelo en `at`.
+    /// Cada color usado se registra como bloque `<name>:<índice>` con su material.
+    pub fn place(&self, reg: &mut BlockRegistry, name: &str, at: Vec3) -> Vec<Aabb> {
+        // z arriba (MagicaVoxel) -> y arriba: (x, y, z) -> (x, z, -y)
+        let world = |p: [i32; 3]| [p[0], p[2], -p[1]];
+        let mut lo = [i32::MAX; 3];
+        for v in &self.voxels {
+            let w = world(v.pos);
+            for i in 0..3 { lo[i] = lo[i].min(w[i]); }
+        }
+
+        let mut ids: [Option<usize>; 256] = [None; 256];
+        let mut cubes = Vec::with_capacity(self.voxels.len());
+        for v in &self.voxels {
+            let id = *ids[v.color as usize].get_or_insert_with(|| self.block_for(reg, name, v.color));
+            let w = world(v.pos);
+            let p = at.add(Vec3::new((w[0] - lo[0]) as f32, (w[1] - lo[1]) as f32, (w[2] - lo[2]) as f32));
+            cubes.push(reg.cube(id, p.x, p.y, p.z));
+        }
+        cubes
+    }
+
+    fn block_for(&self, reg: &mut BlockRegistry, name: &str, color: u8) -> usize {
+        let key = format!("{}:{}", name, color);
+        if let Some(id) = reg.find(&key) { return id; }
+
+        let [r, g, b, a] = self.palette[color as usize];
+        let col = Color::from_u8(r, g, b);
+        reg.add_texture(&key, Texture::Solid(col));
+        let mut m = Material {
+            tex_id: reg.tex(&key), albedo: 1.0, specular: 0.05, transparency: 0.0, reflectivity: 0.0,
+            ior: 1.0, shininess: 16.0, emissive: Color::black(),
+        };
+        if a < 255 { m.transparency = 1.0 - a as f32 / 255.0; }
+
+        // MATL: _metal / _glass / _emit con sus parámetros
+        if let Some(props) = self.materials.get(&color) {
+            let num = |k: &str, d: f32| props.get(k).and_then(|v| v.parse::<f32>().ok()).unwrap_or(d);
+            let rough = num("_rough", 0.5).clamp(0.02, 1.0);
+            m.shininess = (2.0 / (rough * rough)).min(256.0);
+            match props.get("_type").map(String::as_str) {
+                Some("_metal") => {
+                    m.reflectivity = num("_metal", 0.0).clamp(0.0, 1.0) * (1.0 - rough * 0.5);
+                    m.specular = 0.9;
+                }
+                Some("_glass") | Some("_blend") => {
+                    m.transparency = num("_trans", num("_alpha", 0.5)).clamp(0.0, 1.0);
+                    m.reflectivity = 0.05;
+                    m.ior = 1.0 + num("_ior", 0.5);
+                    m.specular = 0.3;
+                }
+                Some("_emit") => {
+                    m.emissive = col.mul(num("_emit", 0.0) * (1.0 + num("_flux", 0.0)));
+                }
+                _ => {}
+            }
+        }
+        reg.material(&key, m);
+        reg.block(&key, &key, Faces::All(&key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rot(r: &str) -> [[i32; 3]; 3] {
+        Xform::from_attrs(&HashMap::from([("_r".to_string(), r.to_string())])).r
+    }
+
+    #[test]
+    fn rotation_bits() {
+        // filas 0 y 1 en las columnas 1 y 0, la 2 en la 2 con signo negativo
+        assert_eq!(rot("65"), [[0, 1, 0], [1, 0, 0], [0, 0, -1]]);
+        assert_eq!(rot("4"), Xform::IDENTITY.r);
+    }
+
+    #[test]
+    fn bad_rotation_is_identity() {
+        // columnas repetidas o fuera de rango: 10 = (2, 2), 15 = (3, 3), 3 = (3, 0)
+        for r in ["10", "15", "3", "255", "0"] {
+            assert_eq!(rot(r), Xform::IDENTITY.r, "_r = {}", r);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::aabb::Aabb;
+use crate::block::{BlockId, BlockRegistry, Shapes};
+use crate::math::Vec3;
+
+/// Bloques que admite una rejilla: a dos bytes por celda, 512 MiB como mucho,
+/// para que una caja mal escrita no agote la memoria.
+pub const MAX_BLOCKS: usize = 1 << 28;
+
+/// Caja de bloques con las dos esquinas incluidas.
+#[derive(Clone, Copy)]
+pub struct BlockBox {
+    pub min: [i32; 3],
+    pub max: [i32; 3],
+}
+
+impl BlockBox {
+    /// Las esquinas pueden venir en cualquier orden.
+    pub fn new(a: [i32; 3], b: [i32; 3]) -> Self {
+        Self { min: [a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2])], max: [a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2])] }
+    }
+
+    /// Caja mínima que contiene las dos.
+    pub fn union(self, o: Self) -> Self {
+        Self::new([0, 1, 2].map(|i| self.min[i].min(o.min[i])), [0, 1, 2].map(|i| self.max[i].max(o.max[i])))
+    }
+
+    /// Número de bloques, o `None` si pasa de `MAX_BLOCKS`.
+    pub fn volume(self) -> Option<usize> {
+        (0..3).try_fold(1usize, |n, i| n.checked_mul((self.max[i] as i64 - self.min[i] as i64 + 1).max(0) as usize))
+            .filter(|&n| n <= MAX_BLOCKS)
+    }
+
+    fn cells(self) -> impl Iterator<Item = [i32; 3]> {
+        let (lo, hi) = (self.min, self.max);
+        (lo[1]..=hi[1]).flat_map(move |y| (lo[2]..=hi[2]).flat_map(move |z| (lo[0]..=hi[0]).map(move |x| [x, y, z])))
+    }
+}
+
+/// Rejilla de bloques de tamaño fijo con esquina mínima en `origin`; `None` es aire.
+#[derive(Clone)]
+pub struct World {
+    origin: [i32; 3],
+    size: [i32; 3],
+    /// `BlockId + 1` por celda y 0 para el aire
+    blocks: Vec<u16>,
+}
+
+fn pack(block: Option<BlockId>) -> u16 {
+    block.map_or(0, |id| u16::try_from(id + 1).expect("la rejilla admite hasta 65535 tipos de bloque"))
+}
+
+fn unpack(cell: u16) -> Option<BlockId> { (cell != 0).then(|| cell as BlockId - 1) }
+
+impl World {
+    pub fn new(sx: i32, sy: i32, sz: i32) -> Result<Self, String> { Self::covering(BlockBox::new([0, 0, 0], [sx - 1, sy - 1, sz - 1])) }
+
+    /// Rejilla justa para la caja, en las coordenadas de la caja; falla si pasa de `MAX_BLOCKS`.
+    pub fn covering(b: BlockBox) -> Result<Self, String> {
+        let n = b.volume().ok_or_else(|| format!("la rejilla pasa de {} bloques", MAX_BLOCKS))?;
+        // cada lado cabe en i32 porque el total ya está acotado
+        let size = [0, 1, 2].map(|i| b.max[i] - b.min[i] + 1);
+        Ok(Self { origin: b.min, size, blocks: vec![0; n] })
+    }
+
+    /// Copia los bloques de `other`, que cubre la misma caja, sin reservar memoria.
+    pub fn copy_from(&mut self, other: &World) {
+        assert!(self.origin == other.origin && self.size == other.size, "rejillas de distinto tamaño");
+        self.blocks.copy_from_slice(&other.blocks);
+    }
+
+    pub fn size(&self) -> [i32; 3] { self.size }
+
+    fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        let [sx, sy, sz] = self.size;
+        let (x, y, z) = (x - self.origin[0], y - self.origin[1], z - self.origin[2]);
+        if x < 0 || y < 0 || z < 0 || x >= sx || y >= sy || z >= sz { return None; }
+        Some(((y * sz + z) * sx + x) as usize)
+    }
+
+    /// Fuera de la rejilla todo es aire.
+    pub fn get_block(&self, x: i32, y: i32, z: i32) -> Option<BlockId> {
+        self.index(x, y, z).and_then(|i| unpack(self.blocks[i]))
+    }
+
+    /// Las escrituras fuera de la rejilla se ignoran.
+    pub fn set_block(&mut self, x: i32, y: i32, z: i32, block: Option<BlockId>) {
+        if let Some(i) = self.index(x, y, z) { self.blocks[i] = pack(block); }
+    }
+
+    pub fn fill(&mut self, b: BlockBox, block: Option<BlockId>) {
+        for [x, y, z] in b.cells() { self.set_block(x, y, z, block); }
+    }
+
+    /// Cambia `from` por `to` dentro de la caja; devuelve cuántos bloques cambió.
+    pub fn replace(&mut self, b: BlockBox, from: Option<BlockId>, to: Option<BlockId>) -> usize {
+        let mut n = 0;
+        for [x, y, z] in b.cells() {
+            if self.index(x, y, z).is_some() && self.get_block(x, y, z) == from {
+                self.set_block(x, y, z, to);
+                n += 1;
+            }
+        }
+        n
+    }
+
+    /// Cáscara de la caja con `block` y el interior vacío.
+    pub fn hollow(&mut self, b: BlockBox, block: Option<BlockId>) {
+        self.fill(b, block);
+        let inner = BlockBox { min: b.min.map(|v| v + 1), max: b.max.map(|v| v - 1) };
+        if (0..3).all(|i| inner.min[i] <= inner.max[i]) { self.fill(inner, None); }
+    }
+
+    /// Geometría de cada bloque según su modelo, con el punto (0, 0, 0) de la rejilla en `at`.
+    pub fn cubes(&self, reg: &BlockRegistry, at: Vec3) -> Vec<Aabb> { self.compile(&reg.shapes(), at) }
+
+    /// Como `cubes`, con la geometría de cada bloque ya sacada del registro
+    /// (`BlockRegistry::shapes`), para recompilar la rejilla cuando el registro ya no está.
+    /// Vallas y paneles se unen a los vecinos que toquen según `Shapes::joins`.
+    pub fn compile(&self, shapes: &Shapes, at: Vec3) -> Vec<Aabb> {
+        let mut cubes = Vec::new();
+        for (i, &cell) in self.blocks.iter().enumerate() {
+            let Some(id) = unpack(cell) else { continue };
+            let [sx, _, sz] = self.size;
+            let i = i as i32;
+            let [ox, oy, oz] = self.origin;
+            let [x, y, z] = [ox + i % sx, oy + i / (sx * sz), oz + i / sx % sz];
+            // un bit por vecino unido, en el orden de `Facing` (norte es -z)
+            let mut links = 0;
+            if shapes.connects(id) {
+                for (bit, (dx, dz)) in [(0, -1), (1, 0), (0, 1), (-1, 0)].into_iter().enumerate() {
+                    if self.get_block(x + dx, y, z + dz).is_some_and(|n| shapes.joins(id, n)) { links |= 1 << bit; }
+                }
+            }
+            let p = Vec3::new(x as f32, y as f32, z as f32).add(at);
+            cubes.extend(shapes.get(id, links).iter().map(|c| {
+                let mut c = c.clone();
+                (c.min, c.max) = (c.min.add(p), c.max.add(p));
+                c
+            }));
+        }
+        cubes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_keep_air_and_ids() {
+        let mut w = World::new(2, 2, 2).unwrap();
+        w.set_block(0, 0, 0, Some(0));
+        w.set_block(1, 1, 1, Some(65534));
+        assert_eq!((w.get_block(0, 0, 0), w.get_block(1, 1, 1), w.get_block(1, 0, 0)), (Some(0), Some(65534), None));
+        let mut copy = World::new(2, 2, 2).unwrap();
+        copy.copy_from(&w);
+        assert_eq!(copy.get_block(1, 1, 1), Some(65534));
+    }
+
+    #[test]
+    fn oversized_box_is_rejected() {
+        assert!(World::covering(BlockBox::new([i32::MIN; 3], [i32::MAX; 3])).is_err());
+        assert_eq!(BlockBox::new([0, 0, 0], [1023, 255, 1023]).volume(), Some(1 << 28));
+    }
+}
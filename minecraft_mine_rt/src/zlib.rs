@@ -0,0 +1,388 @@
+use std::io;
+
+// DEFLATE (RFC 1951) con envolturas zlib (RFC 1950) y gzip (RFC 1952).
+// Decodificador canónico al estilo de `puff.c`: lento pero corto y sin dependencias.
+// El codificador usa LZ77 con cadenas de hash y los códigos Huffman fijos.
+
+fn bad(msg: &str) -> io::Error { io::Error::new(io::ErrorKind::InvalidData, msg.to_string()) }
+
+/// Detecta gzip o zlib por la cabecera; si no, devuelve los bytes tal cual.
+pub fn decompress_auto(data: &[u8]) -> io::Result<Vec<u8>> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        gunzip(data)
+    } else if data.len() >= 2 && data[0] & 0x0f == 8 && (u16::from(data[0]) << 8 | u16::from(data[1])) % 31 == 0 {
+        zlib_decompress(data)
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
+pub fn gunzip(data: &[u8]) -> io::Result<Vec<u8>> {
+    if data.len() < 18 || data[0] != 0x1f || data[1] != 0x8b || data[2] != 8 {
+        return Err(bad("cabecera gzip inválida"));
+    }
+    let flags = data[3];
+    let mut pos = 10;
+    if flags & 0x04 != 0 {
+        // FEXTRA
+        let n = *data.get(pos).ok_or_else(|| bad("gzip truncado"))? as usize
+            | (*data.get(pos + 1).ok_or_else(|| bad("gzip truncado"))? as usize) << 8;
+        pos += 2 + n;
+    }
+    for flag in [0x08, 0x10] {
+        // FNAME, FCOMMENT: cadenas terminadas en cero
+        if flags & flag != 0 {
+            while *data.get(pos).ok_or_else(|| bad("gzip truncado"))? != 0 { pos += 1; }
+            pos += 1;
+        }
+    }
+    if flags & 0x02 != 0 { pos += 2; } // FHCRC
+    let body = data.get(pos..).ok_or_else(|| bad("gzip truncado"))?;
+    let (out, used) = inflate_with_len(body)?;
+    let tail = body.get(used..used + 8).ok_or_else(|| bad("gzip sin CRC"))?;
+    let crc = u32::from_le_bytes(tail[0..4].try_into().unwrap());
+    if crc != crc32(&out) { return Err(bad("CRC de gzip no coincide")); }
+    Ok(out)
+}
+
+pub fn zlib_decompress(data: &[u8]) -> io::Result<Vec<u8>> {
+    if data.len() < 6 || data[0] & 0x0f != 8 || data[1] & 0x20 != 0 {
+        return Err(bad("cabecera zlib inválida"));
+    }
+    let (out, used) = inflate_with_len(&data[2..])?;
+    let tail = data.get(2 + used..2 + used + 4).ok_or_else(|| bad("zlib sin Adler-32"))?;
+    if u32::from_be_bytes(tail.try_into().unwrap()) != adler32(&out) {
+        return Err(bad("Adler-32 de zlib no coincide"));
+    }
+    Ok(out)
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (i, e) in table.iter_mut().enumerate() {
+        let mut c = i as u32;
+        for _ in 0..8 { c = if c & 1 != 0 { 0xedb8_8320 ^ (c >> 1) } else { c >> 1 }; }
+        *e = c;
+    }
+    !data.iter().fold(!0u32, |c, &b| table[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8))
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for &x in chunk {
+            a += x as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    b << 16 | a
+}
+
+/* =======================
+   Inflate
+   ======================= */
+
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+    nbits: u32,
+}
+
+impl Bits<'_> {
+    fn need(&mut self, n: u32) -> io::Result<u32> {
+        while self.nbits < n {
+            let b = *self.data.get(self.pos).ok_or_else(|| bad("deflate truncado"))?;
+            self.pos += 1;
+            self.bit |= (b as u32) << self.nbits;
+            self.nbits += 8;
+        }
+        let v = self.bit & ((1u64 << n) - 1) as u32;
+        self.bit >>= n;
+        self.nbits -= n;
+        Ok(v)
+    }
+}
+
+/// Tabla canónica: cantidad de códigos por longitud y símbolos ordenados.
+struct Huffman {
+    count: [u16; 16],
+    symbol: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> io::Result<Self> {
+        let mut count = [0u16; 16];
+        for &l in lengths { count[l as usize] += 1; }
+        count[0] = 0;
+        let mut offs = [0u16; 16];
+        for i in 1..16 { offs[i] = offs[i - 1] + count[i - 1]; }
+        let mut symbol = vec![0u16; lengths.len()];
+        for (s, &l) in lengths.iter().enumerate() {
+            if l != 0 {
+                symbol[offs[l as usize] as usize] = s as u16;
+                offs[l as usize] += 1;
+            }
+        }
+        // rechaza tablas sobresuscritas
+        let mut left = 1i32;
+        for &c in &count[1..] {
+            left = (left << 1) - c as i32;
+            if left < 0 { return Err(bad("tabla Huffman inválida")); }
+        }
+        Ok(Self { count, symbol })
+    }
+
+    fn decode(&self, bits: &mut Bits) -> io::Result<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..16 {
+            code |= bits.need(1)? as i32;
+            let count = self.count[len] as i32;
+            if code - count < first {
+                return Ok(self.symbol[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first += count;
+            first <<= 1;
+            code <<= 1;
+        }
+        Err(bad("código Huffman inválido"))
+    }
+}
+
+const LEN_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LEN_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145,
+    8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Descomprime y devuelve también los bytes consumidos (para leer la cola gzip/zlib).
+fn inflate_with_len(data: &[u8]) -> io::Result<(Vec<u8>, usize)> {
+    let mut bits = Bits { data, pos: 0, bit: 0, nbits: 0 };
+    let mut out = Vec::with_capacity(data.len() * 4);
+    loop {
+        let last = bits.need(1)?;
+        match bits.need(2)? {
+            0 => {
+                // bloque sin comprimir: alinear a byte
+                bits.bit = 0;
+                bits.nbits = 0;
+                let hdr = data.get(bits.pos..bits.pos + 4).ok_or_else(|| bad("deflate truncado"))?;
+                let len = u16::from_le_bytes([hdr[0], hdr[1]]) as usize;
+                let nlen = u16::from_le_bytes([hdr[2], hdr[3]]) as usize;
+                if len != !nlen & 0xffff { return Err(bad("longitud de bloque almacenado inválida")); }
+                bits.pos += 4;
+                out.extend_from_slice(data.get(bits.pos..bits.pos + len).ok_or_else(|| bad("deflate truncado"))?);
+                bits.pos += len;
+            }
+            1 => {
+                let mut lengths = [0u8; 288];
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+                let lit = Huffman::new(&lengths)?;
+                let dist = Huffman::new(&[5u8; 30])?;
+                codes(&mut bits, &mut out, &lit, &dist)?;
+            }
+            2 => {
+                let nlen = bits.need(5)? as usize + 257;
+                let ndist = bits.need(5)? as usize + 1;
+                let ncode = bits.need(4)? as usize + 4;
+                const ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+                let mut cl = [0u8; 19];
+                for &i in &ORDER[..ncode] { cl[i] = bits.need(3)? as u8; }
+                let clh = Huffman::new(&cl)?;
+                let mut lengths = vec![0u8; nlen + ndist];
+                let mut i = 0;
+                while i < nlen + ndist {
+                    let sym = clh.decode(&mut bits)?;
+                    let (val, rep) = match sym {
+                        0..=15 => (sym as u8, 1),
+                        16 => {
+                            let prev = *lengths.get(i.wrapping_sub(1)).ok_or_else(|| bad("repetición sin longitud previa"))?;
+                            (prev, 3 + bits.need(2)? as usize)
+                        }
+                        17 => (0, 3 + bits.need(3)? as usize),
+                        _ => (0, 11 + bits.need(7)? as usize),
+                    };
+                    if i + rep > lengths.len() { return Err(bad("demasiadas longitudes")); }
+                    lengths[i..i + rep].fill(val);
+                    i += rep;
+                }
+                let lit = Huffman::new(&lengths[..nlen])?;
+                let dist = Huffman::new(&lengths[nlen..])?;
+                codes(&mut bits, &mut out, &lit, &dist)?;
+            }
+            _ => return Err(bad("tipo de bloque deflate inválido")),
+        }
+        if last == 1 { break; }
+    }
+    Ok((out, bits.pos))
+}
+
+fn codes(bits: &mut Bits, out: &mut Vec<u8>, lit: &Huffman, dist: &Huffman) -> io::Result<()> {
+    loop {
+        let sym = lit.decode(bits)? as usize;
+        if sym < 256 {
+            out.push(sym as u8);
+        } else if sym == 256 {
+            return Ok(());
+        } else {
+            let s = sym - 257;
+            if s >= 29 { return Err(bad("longitud inválida")); }
+            let len = LEN_BASE[s] as usize + bits.need(LEN_EXTRA[s] as u32)? as usize;
+            let d = dist.decode(bits)? as usize;
+            if d >= 30 { return Err(bad("distancia inválida")); }
+            let back = DIST_BASE[d] as usize + bits.need(DIST_EXTRA[d] as u32)? as usize;
+            if back > out.len() { return Err(bad("distancia fuera de la ventana")); }
+            let start = out.len() - back;
+            for k in 0..len { out.push(out[start + k]); }
+        }
+    }
+}
+
+/* =======================
+   Deflate
+   ======================= */
+
+/// Escritura de bits empezando por el menos significativo, como pide deflate.
+struct BitWriter {
+    out: Vec<u8>,
+    bit: u32,
+    nbits: u32,
+}
+
+impl BitWriter {
+    fn put(&mut self, v: u32, n: u32) {
+        self.bit |= v << self.nbits;
+        self.nbits += n;
+        while self.nbits >= 8 {
+            self.out.push(self.bit as u8);
+            self.bit >>= 8;
+            self.nbits -= 8;
+        }
+    }
+
+    /// Los códigos Huffman van con el bit más significativo primero.
+    fn code(&mut self, code: u32, len: u32) { self.put(code.reverse_bits() >> (32 - len), len); }
+
+    /// Símbolo de literal/longitud con la tabla fija.
+    fn lit(&mut self, s: u32) {
+        match s {
+            0..=143 => self.code(0x30 + s, 8),
+            144..=255 => self.code(0x190 + s - 144, 9),
+            256..=279 => self.code(s - 256, 7),
+            _ => self.code(0xc0 + s - 280, 8),
+        }
+    }
+}
+
+const WINDOW: usize = 32768;
+const MAX_CHAIN: usize = 64;
+
+/// Un solo bloque con códigos fijos; las repeticiones se buscan en una ventana
+/// de 32 KiB por cadenas de hash de 3 bytes.
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut w = BitWriter { out: Vec::with_capacity(data.len() / 2 + 16), bit: 0, nbits: 0 };
+    w.put(1, 1); // último bloque
+    w.put(1, 2); // códigos fijos
+    let hash = |i: usize| ((data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize) & 0x7fff;
+    let mut head = vec![usize::MAX; 0x8000];
+    let mut prev = vec![usize::MAX; data.len()];
+    let insert = |i: usize, head: &mut [usize], prev: &mut [usize]| {
+        if i + 2 < data.len() {
+            let h = hash(i);
+            prev[i] = head[h];
+            head[h] = i;
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        // la repetición más larga entre las candidatas con el mismo hash
+        let (mut best, mut dist) = (0, 0);
+        if i + 2 < data.len() {
+            let mut j = head[hash(i)];
+            let max = (data.len() - i).min(258);
+            for _ in 0..MAX_CHAIN {
+                if j == usize::MAX || i - j > WINDOW { break; }
+                let len = (0..max).take_while(|&k| data[j + k] == data[i + k]).count();
+                if len > best { (best, dist) = (len, i - j); }
+                if len == max { break; }
+                j = prev[j];
+            }
+        }
+        if best >= 3 {
+            let s = LEN_BASE.iter().rposition(|&b| b as usize <= best).unwrap();
+            w.lit(257 + s as u32);
+            w.put((best - LEN_BASE[s] as usize) as u32, LEN_EXTRA[s] as u32);
+            let d = DIST_BASE.iter().rposition(|&b| b as usize <= dist).unwrap();
+            w.code(d as u32, 5);
+            w.put((dist - DIST_BASE[d] as usize) as u32, DIST_EXTRA[d] as u32);
+            for k in i..i + best { insert(k, &mut head, &mut prev); }
+            i += best;
+        } else {
+            w.lit(data[i] as u32);
+            insert(i, &mut head, &mut prev);
+            i += 1;
+        }
+    }
+    w.lit(256);
+    if w.nbits > 0 { w.out.push(w.bit as u8); }
+    w.out
+}
+
+pub fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x9c];
+    out.extend(deflate(data));
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::Rng;
+
+    fn round_trip(data: &[u8]) {
+        assert_eq!(zlib_decompress(&zlib_compress(data)).unwrap(), data);
+    }
+
+    #[test]
+    fn empty() { round_trip(&[]); }
+
+    #[test]
+    fn short() {
+        round_trip(b"a");
+        round_trip(b"ab");
+        round_trip(b"hola, mundo");
+    }
+
+    #[test]
+    fn repetitive() {
+        // repeticiones de 258 bytes seguidas y distancias hasta el borde de la ventana
+        round_trip(&[0; 100_000]);
+        round_trip(&b"abcabcabd".repeat(20_000));
+        let mut rng = Rng::new(1);
+        let block: Vec<u8> = (0..WINDOW).map(|_| rng.next_u64() as u8).collect();
+        round_trip(&block.repeat(3));
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = Rng::new(42);
+        let data: Vec<u8> = (0..70_000).map(|_| rng.next_u64() as u8).collect();
+        round_trip(&data);
+    }
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}